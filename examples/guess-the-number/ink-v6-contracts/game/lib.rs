@@ -6,6 +6,8 @@ pub mod guess_the_number {
     use ink::prelude::vec::Vec;
     use ink::storage::Mapping;
     use inkv6_client_lib::traits::access_control::*;
+    use inkv6_client_lib::traits::channel::ChannelId;
+    use inkv6_client_lib::traits::dead_letter_queue::*;
     use inkv6_client_lib::traits::kv_store::*;
     use inkv6_client_lib::traits::message_queue::*;
     use inkv6_client_lib::traits::meta_transaction::*;
@@ -55,7 +57,9 @@ pub mod guess_the_number {
     pub struct GuessTheNumber {
         access_control: AccessControlData,
         kv_store: KvStoreData,
+        message_queue: MessageQueueData,
         meta_transaction: MetaTransactionData,
+        dead_letters: DeadLetterData,
         /// game number incrementer
         next_game_number: GameNumber,
         /// current games. Only 1 game by address.
@@ -312,6 +316,38 @@ pub mod guess_the_number {
 
     /// Implement the business logic for the Rollup Client in the 'on_message_received' method
     impl BaseRollupClient for GuessTheNumber {
+        /// the responses for unknown games are kept in the dead letter queue instead of blocking the other responses
+        fn on_reply_rejected(
+            &mut self,
+            channel: ChannelId,
+            letter: DeadLetter,
+        ) -> Result<(), RollupClientError> {
+            self.push_dead_letter(channel, letter)
+        }
+
+        /// the game is cancelled when the player cancels the request of the current attempt
+        fn on_message_cancelled(
+            &mut self,
+            _channel: ChannelId,
+            _id: QueueIndex,
+            message: Vec<u8>,
+        ) -> Result<(), RollupClientError> {
+            let request: RequestMessage = ink::scale::Decode::decode(&mut &message[..])
+                .or(Err(RollupClientError::FailedToDecode))?;
+
+            let player = request.player;
+            if let Some(game) = self.games.get(player) {
+                if game.game_number == request.game_number && game.attempt == request.attempt {
+                    self.games.remove(player);
+                    Self::env().emit_event(GameCancelled {
+                        game_number: game.game_number,
+                        player,
+                    });
+                }
+            }
+            Ok(())
+        }
+
         fn on_message_received(&mut self, action: Vec<u8>) -> Result<(), RollupClientError> {
             // decode the response
             let response: ResponseMessage = ink::scale::Decode::decode(&mut &action[..])
@@ -427,13 +463,74 @@ pub mod guess_the_number {
     impl KvStore for GuessTheNumber {}
 
     /// Boilerplate code to implement the Message Queue
+    impl MessageQueueStorage for GuessTheNumber {
+        fn get_storage(&self) -> &MessageQueueData {
+            &self.message_queue
+        }
+
+        fn get_mut_storage(&mut self) -> &mut MessageQueueData {
+            &mut self.message_queue
+        }
+    }
+
     impl MessageQueue for GuessTheNumber {}
 
+    /// Boilerplate code to implement the dead letter queue
+    impl DeadLetterStorage for GuessTheNumber {
+        fn get_storage(&self) -> &DeadLetterData {
+            &self.dead_letters
+        }
+
+        fn get_mut_storage(&mut self) -> &mut DeadLetterData {
+            &mut self.dead_letters
+        }
+    }
+
+    impl BaseDeadLetterQueue for GuessTheNumber {}
+
+    impl DeadLetterQueue for GuessTheNumber {
+        #[ink(message)]
+        fn get_dead_letters(
+            &self,
+            channel: ChannelId,
+            from: DeadLetterId,
+            limit: DeadLetterId,
+        ) -> Vec<(DeadLetterId, DeadLetter)> {
+            self.inner_get_dead_letters(channel, from, limit)
+        }
+
+        #[ink(message)]
+        fn retry_dead_letter(
+            &mut self,
+            channel: ChannelId,
+            id: DeadLetterId,
+        ) -> Result<(), RollupClientError> {
+            self.inner_retry_dead_letter(channel, id)
+        }
+
+        #[ink(message)]
+        fn discard_dead_letter(
+            &mut self,
+            channel: ChannelId,
+            id: DeadLetterId,
+        ) -> Result<(), RollupClientError> {
+            self.inner_discard_dead_letter(channel, id)
+        }
+    }
+
     /// Boilerplate code to implement the Rollup Client
     impl RollupClient for GuessTheNumber {
         #[ink(message)]
         fn get_value(&self, key: Key) -> Option<Value> {
-            self.inner_get_value(&key)
+            self.inner_get_rollup_value(&key)
+        }
+
+        #[ink(message)]
+        fn get_value_with_meta(&self, key: Key) -> (Option<Value>, Option<KeyMetadata>) {
+            (
+                self.inner_get_rollup_value(&key),
+                self.inner_get_value_metadata(&key),
+            )
         }
 
         #[ink(message)]
@@ -441,6 +538,25 @@ pub mod guess_the_number {
             MessageQueue::has_message(self)
         }
 
+        #[ink(message)]
+        fn get_queue_bounds(&self) -> Result<QueueBounds, RollupClientError> {
+            self.inner_get_queue_bounds()
+        }
+
+        #[ink(message)]
+        fn get_message(&self, id: QueueIndex) -> Result<Option<Vec<u8>>, RollupClientError> {
+            self.inner_get_message(id)
+        }
+
+        #[ink(message)]
+        fn get_messages(
+            &self,
+            from: QueueIndex,
+            limit: QueueIndex,
+        ) -> Result<Vec<(QueueIndex, Vec<u8>)>, RollupClientError> {
+            self.inner_get_messages(from, limit)
+        }
+
         #[ink(message)]
         fn rollup_cond_eq(
             &mut self,
@@ -452,6 +568,23 @@ pub mod guess_the_number {
         }
     }
 
+    impl MessageLifecycle for GuessTheNumber {
+        #[ink(message)]
+        fn prune_queue(&mut self, max: QueueIndex) -> Result<QueueIndex, RollupClientError> {
+            MessageQueue::prune_queue(self, max)
+        }
+
+        #[ink(message)]
+        fn expire(&mut self, id: QueueIndex) -> Result<(), RollupClientError> {
+            self.inner_expire(id)
+        }
+
+        #[ink(message)]
+        fn cancel(&mut self, id: QueueIndex) -> Result<(), RollupClientError> {
+            self.inner_cancel(id)
+        }
+    }
+
     /// Boilerplate code to implement the Meta Transaction
     impl MetaTransactionStorage for GuessTheNumber {
         fn get_storage(&self) -> &MetaTransactionData {
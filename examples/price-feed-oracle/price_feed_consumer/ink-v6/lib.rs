@@ -109,6 +109,7 @@ pub mod price_feed_consumer {
     pub struct PriceFeedConsumer {
         access_control: AccessControlData,
         kv_store: KvStoreData,
+        message_queue: MessageQueueData,
         meta_transaction: MetaTransactionData,
         trading_pairs: Mapping<TradingPairId, TradingPair>,
     }
@@ -197,6 +198,11 @@ pub mod price_feed_consumer {
 
     /// Implement the business logic for the Rollup Client in the 'on_message_received' method
     impl BaseRollupClient for PriceFeedConsumer {
+        /// keep who has written each key and when
+        fn keep_key_metadata(&self) -> bool {
+            true
+        }
+
         fn on_message_received(&mut self, action: Vec<u8>) -> Result<(), RollupClientError> {
 
             // parse the response
@@ -296,13 +302,28 @@ pub mod price_feed_consumer {
     impl KvStore for PriceFeedConsumer {}
 
     /// Boilerplate code to implement the Message Queue
+    impl MessageQueueStorage for PriceFeedConsumer {
+        fn get_storage(&self) -> &MessageQueueData {
+            &self.message_queue
+        }
+
+        fn get_mut_storage(&mut self) -> &mut MessageQueueData {
+            &mut self.message_queue
+        }
+    }
+
     impl MessageQueue for PriceFeedConsumer {}
 
     /// Boilerplate code to implement the Rollup Client
     impl RollupClient for PriceFeedConsumer {
         #[ink(message)]
         fn get_value(&self, key: Key) -> Option<Value> {
-            self.inner_get_value(&key)
+            self.inner_get_rollup_value(&key)
+        }
+
+        #[ink(message)]
+        fn get_value_with_meta(&self, key: Key) -> (Option<Value>, Option<KeyMetadata>) {
+            (self.inner_get_rollup_value(&key), self.inner_get_value_metadata(&key))
         }
 
         #[ink(message)]
@@ -310,6 +331,25 @@ pub mod price_feed_consumer {
             MessageQueue::has_message(self)
         }
 
+        #[ink(message)]
+        fn get_queue_bounds(&self) -> Result<QueueBounds, RollupClientError> {
+            self.inner_get_queue_bounds()
+        }
+
+        #[ink(message)]
+        fn get_message(&self, id: QueueIndex) -> Result<Option<Vec<u8>>, RollupClientError> {
+            self.inner_get_message(id)
+        }
+
+        #[ink(message)]
+        fn get_messages(
+            &self,
+            from: QueueIndex,
+            limit: QueueIndex,
+        ) -> Result<Vec<(QueueIndex, Vec<u8>)>, RollupClientError> {
+            self.inner_get_messages(from, limit)
+        }
+
         #[ink(message)]
         fn rollup_cond_eq(
             &mut self,
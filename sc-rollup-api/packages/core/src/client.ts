@@ -97,13 +97,17 @@ export abstract class Client<KvRawType, ActionRawType, Message, Action> {
     if (this.currentSession.currentIndex == undefined) {
       this.currentSession.currentIndex = await this.getQueueHeadIndex()
     }
-    if (this.currentSession.currentIndex >= tailIndex) {
-      return new None()
+    while (this.currentSession.currentIndex < tailIndex) {
+      const key = this.getMessageKey(this.currentSession.currentIndex)
+      const message = (await this.getRemoteValue(key)).valueOf()
+      this.currentSession.currentIndex += 1
+      this.currentSession.indexUpdated = true
+      // the closed messages (acknowledged, cancelled, expired or failed) are not readable: skip them
+      if (message != undefined) {
+        return Option.of(this.messageCoder.decode(message))
+      }
     }
-    const message = await this.getMessage(this.currentSession.currentIndex)
-    this.currentSession.currentIndex += 1
-    this.currentSession.indexUpdated = true
-    return Option.of(message)
+    return new None()
   }
 
   public async getValue(key: HexString): Promise<Option<HexString>> {
@@ -221,6 +221,35 @@ test("Poll message", async () => {
   expect(tailAgain).toBe(4)
 })
 
+test("Poll message skips the closed messages", async () => {
+  // reset the storage in a know state
+  resetRemoteStorage()
+  // the message 2 is closed (acknowledged, cancelled or expired)
+  remoteValues.delete(simpleTypeCoder.encodeNumber(2, "u32"))
+
+  const client = new MockClient(false)
+
+  await client.startSession()
+
+  const messages = []
+  let message = await client.pollMessage()
+  while (message.isSome()) {
+    messages.push(message.valueOf())
+    message = await client.pollMessage()
+  }
+
+  expect(messages).toEqual([
+    simpleTypeCoder.encodeString("message 1"),
+    simpleTypeCoder.encodeString("message 3"),
+  ])
+
+  await client.commit()
+
+  // the head is moved after the closed message
+  const head = await client.getQueueHeadIndex()
+  expect(head).toBe(4)
+})
+
 test("Feed data", async () => {
   // reset the storage in a know state
   resetRemoteStorage()
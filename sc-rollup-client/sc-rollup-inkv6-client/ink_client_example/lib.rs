@@ -10,13 +10,16 @@ pub mod ink_client {
     use inkv6_client_lib::traits::message_queue::*;
     use inkv6_client_lib::traits::rollup_client::*;
     use inkv6_client_lib::traits::meta_transaction::*;
+    use inkv6_client_lib::traits::request_hub::*;
 
     #[derive(Default, Debug)]
     #[ink(storage)]
     pub struct InkClient {
         access_control: AccessControlData,
         kv_store: KvStoreData,
+        message_queue: MessageQueueData,
         meta_transaction: MetaTransactionData,
+        request_hub: RequestHubData,
     }
 
     impl InkClient {
@@ -58,6 +61,10 @@ pub mod ink_client {
             // implement the business code here
             Ok(())
         }
+
+        fn callback_requester(&mut self, queue: QueueId, id: QueueIndex, response: &[u8]) -> bool {
+            self.call_requester(queue, id, response)
+        }
     }
 
     /// Boilerplate code to implement the access control
@@ -117,13 +124,37 @@ pub mod ink_client {
     impl KvStore for InkClient {}
 
     /// Boilerplate code to implement the Message Queue
-    impl MessageQueue for InkClient {}
+    impl MessageQueueStorage for InkClient {
+        fn get_storage(&self) -> &MessageQueueData {
+            &self.message_queue
+        }
+
+        fn get_mut_storage(&mut self) -> &mut MessageQueueData {
+            &mut self.message_queue
+        }
+    }
+
+    impl MessageQueue for InkClient {
+        fn on_message_closed(
+            &mut self,
+            queue: QueueId,
+            id: QueueIndex,
+        ) -> Result<(), RollupClientError> {
+            self.remove_hub_request(queue, id);
+            Ok(())
+        }
+    }
 
     /// Boilerplate code to implement the Rollup Client
     impl RollupClient for InkClient {
         #[ink(message)]
         fn get_value(&self, key: Key) -> Option<Value> {
-            self.inner_get_value(&key)
+            self.inner_get_rollup_value(&key)
+        }
+
+        #[ink(message)]
+        fn get_value_with_meta(&self, key: Key) -> (Option<Value>, Option<KeyMetadata>) {
+            (self.inner_get_rollup_value(&key), self.inner_get_value_metadata(&key))
         }
 
         #[ink(message)]
@@ -131,6 +162,25 @@ pub mod ink_client {
             MessageQueue::has_message(self)
         }
 
+        #[ink(message)]
+        fn get_queue_bounds(&self) -> Result<QueueBounds, RollupClientError> {
+            self.inner_get_queue_bounds()
+        }
+
+        #[ink(message)]
+        fn get_message(&self, id: QueueIndex) -> Result<Option<Vec<u8>>, RollupClientError> {
+            self.inner_get_message(id)
+        }
+
+        #[ink(message)]
+        fn get_messages(
+            &self,
+            from: QueueIndex,
+            limit: QueueIndex,
+        ) -> Result<Vec<(QueueIndex, Vec<u8>)>, RollupClientError> {
+            self.inner_get_messages(from, limit)
+        }
+
         #[ink(message)]
         fn rollup_cond_eq(
             &mut self,
@@ -174,4 +224,43 @@ pub mod ink_client {
             self.inner_meta_tx_rollup_cond_eq(request, signature)
         }
     }
+
+    /// Boilerplate code to implement the Request Hub
+    impl RequestHubStorage for InkClient {
+        fn get_storage(&self) -> &RequestHubData {
+            &self.request_hub
+        }
+
+        fn get_mut_storage(&mut self) -> &mut RequestHubData {
+            &mut self.request_hub
+        }
+    }
+
+    impl BaseRequestHub for InkClient {}
+
+    impl RequestHub for InkClient {
+        #[ink(message)]
+        fn request(
+            &mut self,
+            payload: Vec<u8>,
+            callback_selector: CallbackSelector,
+        ) -> Result<QueueIndex, RollupClientError> {
+            self.inner_request(payload, callback_selector)
+        }
+
+        #[ink(message)]
+        fn get_hub_request(&self, id: QueueIndex) -> Option<HubRequest> {
+            self.inner_get_hub_request(id)
+        }
+
+        #[ink(message)]
+        fn get_callback_limits(&self) -> CallbackLimits {
+            self.inner_get_callback_limits()
+        }
+
+        #[ink(message)]
+        fn set_callback_limits(&mut self, limits: CallbackLimits) -> Result<(), RollupClientError> {
+            self.inner_set_callback_limits(limits)
+        }
+    }
 }
@@ -0,0 +1,266 @@
+mod contract;
+mod test_utils;
+
+use contract::test_contract::InkClient;
+use ink::scale::Encode;
+use inkv6_client_lib::traits::access_control::{AccessControl, AccessControlError, ADMIN_ROLE};
+use inkv6_client_lib::traits::channel::*;
+use inkv6_client_lib::traits::dead_letter_queue::DeadLetterQueue;
+use inkv6_client_lib::traits::kv_store::KvStore;
+use inkv6_client_lib::traits::message_queue::MessageQueue;
+use inkv6_client_lib::traits::rollup_client::*;
+use inkv6_client_lib::traits::RollupClientError;
+use test_utils::{accounts, change_caller};
+
+const CHANNEL: ChannelId = 1;
+
+#[ink::test]
+fn test_channel_roles() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+
+    // only the admin can grant a role in a channel
+    change_caller(accounts.bob);
+    assert_eq!(
+        Err(RollupClientError::AccessControlError(
+            AccessControlError::MissingRole
+        )),
+        contract.grant_channel_role(CHANNEL, ATTESTOR_ROLE, accounts.bob)
+    );
+    change_caller(accounts.alice);
+    assert_eq!(
+        Ok(()),
+        contract.grant_channel_role(CHANNEL, ATTESTOR_ROLE, accounts.bob)
+    );
+
+    // the role is granted only in this channel
+    assert_eq!(
+        true,
+        contract.has_channel_role(CHANNEL, ATTESTOR_ROLE, accounts.bob)
+    );
+    assert_eq!(
+        false,
+        contract.has_channel_role(2, ATTESTOR_ROLE, accounts.bob)
+    );
+    assert_eq!(false, contract.has_role(ATTESTOR_ROLE, accounts.bob));
+
+    // the roles of the default channel and the admins are not granted in the other channels
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.charlie));
+    assert_eq!(
+        true,
+        contract.has_channel_role(DEFAULT_CHANNEL, ATTESTOR_ROLE, accounts.charlie)
+    );
+    assert_eq!(
+        false,
+        contract.has_channel_role(CHANNEL, ATTESTOR_ROLE, accounts.charlie)
+    );
+    assert_eq!(
+        true,
+        contract.has_channel_role(CHANNEL, ADMIN_ROLE, accounts.alice)
+    );
+
+    assert_eq!(
+        Ok(()),
+        contract.revoke_channel_role(CHANNEL, ATTESTOR_ROLE, accounts.bob)
+    );
+    assert_eq!(
+        false,
+        contract.has_channel_role(CHANNEL, ATTESTOR_ROLE, accounts.bob)
+    );
+}
+
+#[ink::test]
+fn test_channel_attestors_are_isolated() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+    assert_eq!(
+        Ok(()),
+        contract.grant_channel_role(CHANNEL, ATTESTOR_ROLE, accounts.bob)
+    );
+
+    // alice is not attestor of the channel
+    assert_eq!(
+        Err(RollupClientError::AccessControlError(
+            AccessControlError::MissingRole
+        )),
+        contract.channel_rollup_cond_eq(CHANNEL, vec![], vec![], vec![])
+    );
+
+    // bob is not attestor of the default channel
+    change_caller(accounts.bob);
+    assert_eq!(
+        Err(RollupClientError::AccessControlError(
+            AccessControlError::MissingRole
+        )),
+        contract.rollup_cond_eq(vec![], vec![], vec![])
+    );
+    assert_eq!(
+        Ok(()),
+        contract.channel_rollup_cond_eq(CHANNEL, vec![], vec![], vec![])
+    );
+
+    // bob grants charlie as attestor of the channel
+    let actions = vec![HandleActionInput::GrantAttestor(accounts.charlie)];
+    assert_eq!(
+        Ok(()),
+        contract.channel_rollup_cond_eq(CHANNEL, vec![], vec![], actions)
+    );
+    assert_eq!(
+        true,
+        contract.has_channel_role(CHANNEL, ATTESTOR_ROLE, accounts.charlie)
+    );
+    assert_eq!(false, contract.has_role(ATTESTOR_ROLE, accounts.charlie));
+}
+
+#[ink::test]
+fn test_channel_values_are_isolated() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+    assert_eq!(
+        Ok(()),
+        contract.grant_channel_role(CHANNEL, ATTESTOR_ROLE, accounts.alice)
+    );
+
+    let key = 123u8.encode();
+    let updates = vec![(key.clone(), Some(456u128.encode()))];
+    assert_eq!(
+        Ok(()),
+        contract.channel_rollup_cond_eq(CHANNEL, vec![], updates, vec![])
+    );
+
+    assert_eq!(
+        Some(456u128.encode()),
+        contract.get_channel_value(CHANNEL, key.clone())
+    );
+    assert_eq!(None, contract.get_value(key.clone()));
+    assert_eq!(None, contract.get_channel_value(2, key.clone()));
+
+    // the conditions are checked in the channel
+    let conditions = vec![(key.clone(), None)];
+    assert_eq!(
+        Ok(()),
+        contract.rollup_cond_eq(conditions.clone(), vec![], vec![])
+    );
+    assert_eq!(
+        Err(RollupClientError::ConditionNotMet(0)),
+        contract.channel_rollup_cond_eq(CHANNEL, conditions.clone(), vec![], vec![])
+    );
+    assert_eq!(
+        Ok(vec![ConditionResult {
+            is_met: false,
+            current_value: Some(456u128.encode())
+        }]),
+        contract.check_channel_conditions(CHANNEL, conditions.clone())
+    );
+    assert_eq!(
+        Ok(vec![ConditionResult {
+            is_met: true,
+            current_value: None
+        }]),
+        contract.check_conditions(conditions)
+    );
+
+    // the default channel can't access the data of the other channels
+    let updates = vec![(get_channel_key(CHANNEL, &key), None)];
+    assert_eq!(
+        Err(RollupClientError::ReservedKey),
+        contract.rollup_cond_eq(vec![], updates, vec![])
+    );
+    assert_eq!(
+        Some(456u128.encode()),
+        contract.get_channel_value(CHANNEL, key)
+    );
+}
+
+#[ink::test]
+fn test_channel_queues_are_isolated() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+    assert_eq!(
+        Ok(()),
+        contract.grant_channel_role(CHANNEL, ATTESTOR_ROLE, accounts.alice)
+    );
+
+    let message = 4589u16;
+    assert_eq!(Ok(0), contract.push_channel_message(CHANNEL, &message));
+    assert_eq!(Ok(1), contract.push_channel_message(CHANNEL, &message));
+    assert_eq!(Ok(0), contract.push_message(&message));
+
+    assert_eq!(
+        Ok(true),
+        MessageQueue::has_channel_message(&contract, CHANNEL)
+    );
+    assert_eq!(Ok(2), contract.get_channel_queue_tail(CHANNEL));
+    assert_eq!(Ok(1), contract.get_queue_tail());
+
+    // the head of the default channel can't go beyond its own tail
+    let actions = vec![HandleActionInput::SetQueueHead(2)];
+    assert_eq!(
+        Err(RollupClientError::InvalidPopTarget),
+        contract.rollup_cond_eq(vec![], vec![], actions.clone())
+    );
+
+    assert_eq!(
+        Ok(()),
+        contract.channel_rollup_cond_eq(CHANNEL, vec![], vec![], actions)
+    );
+    assert_eq!(
+        Ok(false),
+        MessageQueue::has_channel_message(&contract, CHANNEL)
+    );
+    assert_eq!(Ok(2), contract.get_channel_queue_head(CHANNEL));
+    assert_eq!(Ok(0), contract.get_queue_head());
+    assert_eq!(Ok(true), MessageQueue::has_message(&contract));
+
+    // the replies are dispatched with the channel
+    let actions = vec![HandleActionInput::Reply(012u8.encode())];
+    assert_eq!(
+        Ok(()),
+        contract.channel_rollup_cond_eq(CHANNEL, vec![], vec![], actions)
+    );
+}
+
+#[ink::test]
+fn test_default_channel_hooks() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+    assert_eq!(
+        Ok(()),
+        contract.grant_channel_role(CHANNEL, ATTESTOR_ROLE, accounts.alice)
+    );
+
+    // the contract rejects this reply in `on_message_received`
+    let rejected = 1u8.encode();
+    contract
+        .inner_set_value(&b"app/rejected".to_vec(), Some(&rejected))
+        .unwrap();
+
+    // the messages of the default channel are handled by `on_message_received`
+    let actions = vec![HandleActionInput::Reply(rejected)];
+    assert_eq!(
+        Ok(()),
+        contract.channel_rollup_cond_eq(DEFAULT_CHANNEL, vec![], vec![], actions.clone())
+    );
+    assert_eq!(1, contract.get_dead_letters(DEFAULT_CHANNEL, 0, 10).len());
+
+    // the messages of the other channels are handled by `on_channel_message_received`
+    assert_eq!(
+        Ok(()),
+        contract.channel_rollup_cond_eq(CHANNEL, vec![], vec![], actions)
+    );
+    assert_eq!(0, contract.get_dead_letters(CHANNEL, 0, 10).len());
+}
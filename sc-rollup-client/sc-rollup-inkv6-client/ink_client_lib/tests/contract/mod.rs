@@ -2,18 +2,53 @@
 
 #[ink::contract]
 pub mod test_contract {
+    use ink::scale::Encode;
     use inkv6_client_lib::traits::access_control::{
         AccessControl, AccessControlData, AccessControlError, AccessControlStorage,
         BaseAccessControl, RoleType,
     };
-    use inkv6_client_lib::traits::kv_store::{Key, KvStore, KvStoreData, KvStoreStorage, Value};
-    use inkv6_client_lib::traits::message_queue::MessageQueue;
+    use inkv6_client_lib::traits::channel::{
+        BaseMultiChannelRollupClient, ChannelId, MultiChannelRollupClient,
+    };
+    use inkv6_client_lib::traits::dead_letter_queue::{
+        BaseDeadLetterQueue, DeadLetter, DeadLetterData, DeadLetterId, DeadLetterQueue,
+        DeadLetterStorage,
+    };
+    use inkv6_client_lib::traits::encrypted_payload::{
+        BaseEncryptedMessageQueue, EncryptedMessageQueue, EncryptionKeyData, EncryptionKeyStorage,
+        PublicKey, WorkerPublicKey,
+    };
+    use inkv6_client_lib::traits::fee_escrow::{
+        BaseFeeEscrow, Escrow, Fee, FeeEscrow, FeeEscrowData, FeeEscrowStorage, FeeTarget,
+    };
+    use inkv6_client_lib::traits::kv_store::{
+        BaseKvStoreQuota, Key, KeyMetadata, KvStore, KvStoreData, KvStoreLimits, KvStoreQuota,
+        KvStoreStorage, KvStoreUsageReport, Value,
+    };
+    use inkv6_client_lib::traits::message_queue::{
+        BaseMessageQueueMigration, BaseMessageQueueQuota, MessageEnvelope, MessageLease,
+        MessageQueue, MessageQueueData, MessageQueueMigration, MessageQueueQuota,
+        MessageQueueStorage, QueueBounds, QueueId, QueueIndex, QueueLimits, QueueStats, Topic,
+    };
     use inkv6_client_lib::traits::meta_transaction::{
         BaseMetaTransaction, ForwardRequest, MetaTransaction, MetaTransactionData,
         MetaTransactionStorage,
     };
+    use inkv6_client_lib::traits::reply_quorum::{
+        BaseReplyQuorum, CandidateReply, PendingReplies, QuorumConfig, ReplyQuorum,
+        ReplyQuorumData, ReplyQuorumStorage,
+    };
+    use inkv6_client_lib::traits::request_hub::{
+        BaseRequestHub, CallbackLimits, CallbackSelector, HubRequest, RequestHub, RequestHubData,
+        RequestHubStorage,
+    };
     use inkv6_client_lib::traits::rollup_client::{
-        BaseRollupClient, HandleActionInput, RollupClient,
+        BaseRollupClient, ConditionChecker, ConditionResult, HandleActionInput, MessageLifecycle,
+        MessageQueueReader, RollupClient,
+    };
+    use inkv6_client_lib::traits::scheduled_request::{
+        BaseScheduledRequests, Schedule, ScheduleData, ScheduleId, ScheduleStorage,
+        ScheduledRequests,
     };
     use inkv6_client_lib::traits::RollupClientError;
 
@@ -22,7 +57,14 @@ pub mod test_contract {
     pub struct InkClient {
         access_control: AccessControlData,
         kv_store: KvStoreData,
+        message_queue: MessageQueueData,
         meta_transaction: MetaTransactionData,
+        dead_letters: DeadLetterData,
+        encryption: EncryptionKeyData,
+        fee_escrow: FeeEscrowData,
+        hub: RequestHubData,
+        quorum: ReplyQuorumData,
+        schedules: ScheduleData,
     }
 
     impl InkClient {
@@ -36,7 +78,127 @@ pub mod test_contract {
 
     /// Implement the business logic for the Rollup Client in the 'on_message_received' method
     impl BaseRollupClient for InkClient {
-        fn on_message_received(&mut self, _action: Vec<u8>) -> Result<(), RollupClientError> {
+        fn keep_key_metadata(&self) -> bool {
+            true
+        }
+
+        fn collect_reply(
+            &mut self,
+            queue: QueueId,
+            attestor: Address,
+            id: QueueIndex,
+            payload: Vec<u8>,
+        ) -> Result<Option<Vec<CandidateReply>>, RollupClientError> {
+            self.inner_collect_reply(queue, attestor, id, payload)
+        }
+
+        fn take_timed_out_replies(
+            &mut self,
+            queue: QueueId,
+            id: QueueIndex,
+        ) -> Result<Vec<CandidateReply>, RollupClientError> {
+            self.inner_take_timed_out_replies(queue, id)
+        }
+
+        fn aggregate(
+            &mut self,
+            _channel: ChannelId,
+            replies: Vec<CandidateReply>,
+        ) -> Result<Vec<u8>, RollupClientError> {
+            self.inner_aggregate(&replies)
+        }
+
+        fn on_message_answered(
+            &mut self,
+            queue: QueueId,
+            id: QueueIndex,
+            attestors: Vec<Address>,
+        ) -> Result<(), RollupClientError> {
+            self.release_fee(queue, id, &attestors)?;
+            self.complete_scheduled_request(queue, id);
+            Ok(())
+        }
+
+        fn on_messages_consumed(
+            &mut self,
+            queue: QueueId,
+            attestor: Address,
+            from: QueueIndex,
+            to: QueueIndex,
+        ) -> Result<(), RollupClientError> {
+            self.record_consumer(queue, attestor, from, to);
+            Ok(())
+        }
+
+        fn on_reply_rejected(
+            &mut self,
+            channel: ChannelId,
+            letter: DeadLetter,
+        ) -> Result<(), RollupClientError> {
+            self.push_dead_letter(channel, letter)
+        }
+
+        fn callback_requester(&mut self, queue: QueueId, id: QueueIndex, response: &[u8]) -> bool {
+            self.call_requester(queue, id, response)
+        }
+
+        fn on_rollup_applied(&mut self, channel: ChannelId) -> Result<(), RollupClientError> {
+            self.push_due_requests_on_rollup(channel)
+        }
+
+        fn on_message_received(&mut self, action: Vec<u8>) -> Result<(), RollupClientError> {
+            // reject the message saved under the key 'app/rejected' to test the dead letter queue
+            if self.inner_get_value(&b"app/rejected".to_vec()) == Some(action) {
+                return Err(RollupClientError::BusinessError(1));
+            }
+            Ok(())
+        }
+
+        fn on_reply(
+            &mut self,
+            request: Vec<u8>,
+            response: Vec<u8>,
+        ) -> Result<(), RollupClientError> {
+            self.on_message_received(response.clone())?;
+            // keep the last reply to check the hook is called with the request
+            self.inner_set_value(&b"app/reply".to_vec(), Some(&(request, response).encode()))
+        }
+
+        fn on_message_expired(
+            &mut self,
+            _channel: ChannelId,
+            id: QueueIndex,
+            _message: Vec<u8>,
+        ) -> Result<(), RollupClientError> {
+            // keep the last expired message to check the hook is called
+            self.inner_set_value(&b"app/expired".to_vec(), Some(&id.encode()))
+        }
+
+        fn on_message_cancelled(
+            &mut self,
+            _channel: ChannelId,
+            id: QueueIndex,
+            _message: Vec<u8>,
+        ) -> Result<(), RollupClientError> {
+            // keep the last cancelled message to check the hook is called
+            self.inner_set_value(&b"app/cancelled".to_vec(), Some(&id.encode()))
+        }
+
+        fn on_message_failed(
+            &mut self,
+            _channel: ChannelId,
+            id: QueueIndex,
+            _message: Vec<u8>,
+        ) -> Result<(), RollupClientError> {
+            // keep the last failed request to check the hook is called
+            self.inner_set_value(&b"app/failed".to_vec(), Some(&id.encode()))
+        }
+
+        fn on_channel_message_received(
+            &mut self,
+            _channel: ChannelId,
+            _action: Vec<u8>,
+        ) -> Result<(), RollupClientError> {
             // implement the business code here
             Ok(())
         }
@@ -96,16 +258,320 @@ pub mod test_contract {
         }
     }
 
-    impl KvStore for InkClient {}
+    impl KvStore for InkClient {
+        fn reserved_prefixes(&self) -> &[&[u8]] {
+            &[b"app/"]
+        }
+    }
+
+    impl BaseKvStoreQuota for InkClient {}
+
+    impl KvStoreQuota for InkClient {
+        #[ink(message)]
+        fn kv_store_usage(&self) -> KvStoreUsageReport {
+            self.inner_kv_store_usage()
+        }
+
+        #[ink(message)]
+        fn get_kv_store_limits(&self) -> KvStoreLimits {
+            self.inner_get_kv_store_limits()
+        }
+
+        #[ink(message)]
+        fn set_kv_store_limits(&mut self, limits: KvStoreLimits) -> Result<(), RollupClientError> {
+            self.inner_set_kv_store_limits(limits)
+        }
+    }
 
     /// Boilerplate code to implement the Message Queue
-    impl MessageQueue for InkClient {}
+    impl MessageQueueStorage for InkClient {
+        fn get_storage(&self) -> &MessageQueueData {
+            &self.message_queue
+        }
+
+        fn get_mut_storage(&mut self) -> &mut MessageQueueData {
+            &mut self.message_queue
+        }
+    }
+
+    impl MessageQueue for InkClient {
+        fn on_message_closed(
+            &mut self,
+            queue: QueueId,
+            id: QueueIndex,
+        ) -> Result<(), RollupClientError> {
+            // release the resources which can't fail first, so they are not kept if the fee can't be settled
+            self.remove_hub_request(queue, id);
+            self.remove_pending_replies(queue, id);
+            self.remove_scheduled_request(queue, id);
+            self.settle_fee(queue, id)
+        }
+    }
+
+    impl BaseMessageQueueQuota for InkClient {}
+
+    impl MessageQueueQuota for InkClient {
+        #[ink(message)]
+        fn queue_stats(&self) -> Result<QueueStats, RollupClientError> {
+            self.inner_queue_stats()
+        }
+
+        #[ink(message)]
+        fn get_queue_limits(&self) -> QueueLimits {
+            self.inner_get_queue_limits()
+        }
+
+        #[ink(message)]
+        fn set_queue_limits(&mut self, limits: QueueLimits) -> Result<(), RollupClientError> {
+            self.inner_set_queue_limits(limits)
+        }
+    }
+
+    impl BaseMessageQueueMigration for InkClient {}
+
+    impl MessageQueueMigration for InkClient {
+        #[ink(message)]
+        fn migrate_message_queue(
+            &mut self,
+            channel: ChannelId,
+            max: QueueIndex,
+        ) -> Result<QueueIndex, RollupClientError> {
+            self.inner_migrate_message_queue(channel, max)
+        }
+    }
+
+    /// Boilerplate code to implement the encrypted payloads
+    impl EncryptionKeyStorage for InkClient {
+        fn get_storage(&self) -> &EncryptionKeyData {
+            &self.encryption
+        }
+
+        fn get_mut_storage(&mut self) -> &mut EncryptionKeyData {
+            &mut self.encryption
+        }
+    }
+
+    impl BaseEncryptedMessageQueue for InkClient {}
+
+    impl EncryptedMessageQueue for InkClient {
+        #[ink(message)]
+        fn get_worker_public_key(&self) -> Option<WorkerPublicKey> {
+            self.inner_get_worker_public_key()
+        }
+
+        #[ink(message)]
+        fn set_worker_public_key(
+            &mut self,
+            public_key: PublicKey,
+        ) -> Result<(), RollupClientError> {
+            self.inner_set_worker_public_key(public_key)
+        }
+    }
+
+    /// Boilerplate code to implement the fee escrow
+    impl FeeEscrowStorage for InkClient {
+        fn get_storage(&self) -> &FeeEscrowData {
+            &self.fee_escrow
+        }
+
+        fn get_mut_storage(&mut self) -> &mut FeeEscrowData {
+            &mut self.fee_escrow
+        }
+    }
+
+    impl BaseFeeEscrow for InkClient {}
+
+    impl FeeEscrow for InkClient {
+        #[ink(message)]
+        fn get_fee(&self, target: FeeTarget) -> Option<Fee> {
+            self.inner_get_fee(target)
+        }
+
+        #[ink(message)]
+        fn set_fee(
+            &mut self,
+            target: FeeTarget,
+            fee: Option<Fee>,
+        ) -> Result<(), RollupClientError> {
+            self.inner_set_fee(target, fee)
+        }
+
+        #[ink(message)]
+        fn get_escrow(&self, id: QueueIndex) -> Option<Escrow> {
+            self.inner_get_escrow(id)
+        }
+
+        #[ink(message)]
+        fn set_payout_address(&mut self, payout_address: Option<Address>) {
+            self.inner_set_payout_address(payout_address)
+        }
+
+        #[ink(message)]
+        fn get_claimable_fees(&self, account: Address) -> Fee {
+            self.inner_get_claimable_fees(account)
+        }
+
+        #[ink(message)]
+        fn claim_fees(&mut self) -> Result<Fee, RollupClientError> {
+            self.inner_claim_fees()
+        }
+    }
+
+    /// Boilerplate code to implement the request hub
+    impl RequestHubStorage for InkClient {
+        fn get_storage(&self) -> &RequestHubData {
+            &self.hub
+        }
+
+        fn get_mut_storage(&mut self) -> &mut RequestHubData {
+            &mut self.hub
+        }
+    }
+
+    impl BaseRequestHub for InkClient {}
+
+    impl RequestHub for InkClient {
+        #[ink(message)]
+        fn request(
+            &mut self,
+            payload: Vec<u8>,
+            callback_selector: CallbackSelector,
+        ) -> Result<QueueIndex, RollupClientError> {
+            self.inner_request(payload, callback_selector)
+        }
+
+        #[ink(message)]
+        fn get_hub_request(&self, id: QueueIndex) -> Option<HubRequest> {
+            self.inner_get_hub_request(id)
+        }
+
+        #[ink(message)]
+        fn get_callback_limits(&self) -> CallbackLimits {
+            self.inner_get_callback_limits()
+        }
+
+        #[ink(message)]
+        fn set_callback_limits(&mut self, limits: CallbackLimits) -> Result<(), RollupClientError> {
+            self.inner_set_callback_limits(limits)
+        }
+    }
+
+    /// Boilerplate code to implement the scheduled requests
+    impl ScheduleStorage for InkClient {
+        fn get_storage(&self) -> &ScheduleData {
+            &self.schedules
+        }
+
+        fn get_mut_storage(&mut self) -> &mut ScheduleData {
+            &mut self.schedules
+        }
+    }
+
+    impl BaseScheduledRequests for InkClient {}
+
+    impl ScheduledRequests for InkClient {
+        #[ink(message)]
+        fn get_schedule(&self, schedule_id: ScheduleId) -> Option<Schedule> {
+            self.inner_get_schedule(schedule_id)
+        }
+
+        #[ink(message)]
+        fn push_due_requests(&mut self, max: u32) -> Result<u32, RollupClientError> {
+            self.inner_push_due_requests(max)
+        }
+
+        #[ink(message)]
+        fn cancel_schedule(&mut self, schedule_id: ScheduleId) -> Result<(), RollupClientError> {
+            self.inner_cancel_schedule(schedule_id)
+        }
+    }
+
+    /// Boilerplate code to implement the reply quorum
+    impl ReplyQuorumStorage for InkClient {
+        fn get_storage(&self) -> &ReplyQuorumData {
+            &self.quorum
+        }
+
+        fn get_mut_storage(&mut self) -> &mut ReplyQuorumData {
+            &mut self.quorum
+        }
+    }
+
+    impl BaseReplyQuorum for InkClient {}
+
+    impl ReplyQuorum for InkClient {
+        #[ink(message)]
+        fn get_quorum_config(&self) -> QuorumConfig {
+            self.inner_get_quorum_config()
+        }
+
+        #[ink(message)]
+        fn set_quorum_config(&mut self, config: QuorumConfig) -> Result<(), RollupClientError> {
+            self.inner_set_quorum_config(config)
+        }
+
+        #[ink(message)]
+        fn get_pending_replies(&self, id: QueueIndex) -> Option<PendingReplies> {
+            self.inner_get_pending_replies(id)
+        }
+    }
+
+    /// Boilerplate code to implement the dead letter queue
+    impl DeadLetterStorage for InkClient {
+        fn get_storage(&self) -> &DeadLetterData {
+            &self.dead_letters
+        }
+
+        fn get_mut_storage(&mut self) -> &mut DeadLetterData {
+            &mut self.dead_letters
+        }
+    }
+
+    impl BaseDeadLetterQueue for InkClient {}
+
+    impl DeadLetterQueue for InkClient {
+        #[ink(message)]
+        fn get_dead_letters(
+            &self,
+            channel: ChannelId,
+            from: DeadLetterId,
+            limit: DeadLetterId,
+        ) -> Vec<(DeadLetterId, DeadLetter)> {
+            self.inner_get_dead_letters(channel, from, limit)
+        }
+
+        #[ink(message)]
+        fn retry_dead_letter(
+            &mut self,
+            channel: ChannelId,
+            id: DeadLetterId,
+        ) -> Result<(), RollupClientError> {
+            self.inner_retry_dead_letter(channel, id)
+        }
+
+        #[ink(message)]
+        fn discard_dead_letter(
+            &mut self,
+            channel: ChannelId,
+            id: DeadLetterId,
+        ) -> Result<(), RollupClientError> {
+            self.inner_discard_dead_letter(channel, id)
+        }
+    }
 
     /// Boilerplate code to implement the Rollup Client
     impl RollupClient for InkClient {
         #[ink(message)]
         fn get_value(&self, key: Key) -> Option<Value> {
-            self.inner_get_value(&key)
+            self.inner_get_rollup_value(&key)
+        }
+
+        #[ink(message)]
+        fn get_value_with_meta(&self, key: Key) -> (Option<Value>, Option<KeyMetadata>) {
+            (
+                self.inner_get_rollup_value(&key),
+                self.inner_get_value_metadata(&key),
+            )
         }
 
         #[ink(message)]
@@ -113,6 +579,25 @@ pub mod test_contract {
             MessageQueue::has_message(self)
         }
 
+        #[ink(message)]
+        fn get_queue_bounds(&self) -> Result<QueueBounds, RollupClientError> {
+            self.inner_get_queue_bounds()
+        }
+
+        #[ink(message)]
+        fn get_message(&self, id: QueueIndex) -> Result<Option<Vec<u8>>, RollupClientError> {
+            self.inner_get_message(id)
+        }
+
+        #[ink(message)]
+        fn get_messages(
+            &self,
+            from: QueueIndex,
+            limit: QueueIndex,
+        ) -> Result<Vec<(QueueIndex, Vec<u8>)>, RollupClientError> {
+            self.inner_get_messages(from, limit)
+        }
+
         #[ink(message)]
         fn rollup_cond_eq(
             &mut self,
@@ -124,6 +609,150 @@ pub mod test_contract {
         }
     }
 
+    impl MessageQueueReader for InkClient {
+        #[ink(message)]
+        fn get_topic_queue_bounds(&self, topic: Topic) -> Result<QueueBounds, RollupClientError> {
+            self.inner_get_topic_queue_bounds(topic)
+        }
+
+        #[ink(message)]
+        fn get_topic_messages(
+            &self,
+            topic: Topic,
+            from: QueueIndex,
+            limit: QueueIndex,
+        ) -> Result<Vec<(QueueIndex, Vec<u8>)>, RollupClientError> {
+            self.inner_get_topic_messages(topic, from, limit)
+        }
+
+        #[ink(message)]
+        fn get_message_lease(&self, id: QueueIndex) -> Option<MessageLease> {
+            MessageQueue::get_message_lease(self, id)
+        }
+
+        #[ink(message)]
+        fn get_message_envelope(
+            &self,
+            id: QueueIndex,
+        ) -> Result<Option<MessageEnvelope>, RollupClientError> {
+            MessageQueue::get_message_envelope(self, id)
+        }
+    }
+
+    impl MessageLifecycle for InkClient {
+        #[ink(message)]
+        fn prune_queue(&mut self, max: QueueIndex) -> Result<QueueIndex, RollupClientError> {
+            MessageQueue::prune_queue(self, max)
+        }
+
+        #[ink(message)]
+        fn expire(&mut self, id: QueueIndex) -> Result<(), RollupClientError> {
+            self.inner_expire(id)
+        }
+
+        #[ink(message)]
+        fn cancel(&mut self, id: QueueIndex) -> Result<(), RollupClientError> {
+            self.inner_cancel(id)
+        }
+
+        #[ink(message)]
+        fn expire_topic_message(
+            &mut self,
+            topic: Topic,
+            id: QueueIndex,
+        ) -> Result<(), RollupClientError> {
+            self.inner_expire_topic_message(topic, id)
+        }
+
+        #[ink(message)]
+        fn cancel_topic_message(
+            &mut self,
+            topic: Topic,
+            id: QueueIndex,
+        ) -> Result<(), RollupClientError> {
+            self.inner_cancel_topic_message(topic, id)
+        }
+    }
+
+    impl ConditionChecker for InkClient {
+        #[ink(message)]
+        fn check_conditions(
+            &self,
+            conditions: Vec<(Key, Option<Value>)>,
+        ) -> Result<Vec<ConditionResult>, RollupClientError> {
+            self.inner_check_conditions(conditions)
+        }
+
+        #[ink(message)]
+        fn check_channel_conditions(
+            &self,
+            channel: ChannelId,
+            conditions: Vec<(Key, Option<Value>)>,
+        ) -> Result<Vec<ConditionResult>, RollupClientError> {
+            self.inner_check_channel_conditions(channel, conditions)
+        }
+    }
+
+    /// Boilerplate code to implement the Rollup Client with several channels
+    impl BaseMultiChannelRollupClient for InkClient {}
+
+    impl MultiChannelRollupClient for InkClient {
+        #[ink(message)]
+        fn get_channel_value(&self, channel: ChannelId, key: Key) -> Option<Value> {
+            self.inner_get_rollup_channel_value(channel, &key)
+        }
+
+        #[ink(message)]
+        fn has_channel_message(&self, channel: ChannelId) -> Result<bool, RollupClientError> {
+            MessageQueue::has_channel_message(self, channel)
+        }
+
+        #[ink(message)]
+        fn prune_channel_queue(
+            &mut self,
+            channel: ChannelId,
+            max: QueueIndex,
+        ) -> Result<QueueIndex, RollupClientError> {
+            self.inner_prune_channel_queue(channel, max)
+        }
+
+        #[ink(message)]
+        fn channel_rollup_cond_eq(
+            &mut self,
+            channel: ChannelId,
+            conditions: Vec<(Key, Option<Value>)>,
+            updates: Vec<(Key, Option<Value>)>,
+            actions: Vec<HandleActionInput>,
+        ) -> Result<(), RollupClientError> {
+            self.inner_channel_rollup_cond_eq(channel, conditions, updates, actions)
+        }
+
+        #[ink(message)]
+        fn has_channel_role(&self, channel: ChannelId, role: RoleType, account: Address) -> bool {
+            self.inner_has_channel_role(channel, role, account)
+        }
+
+        #[ink(message)]
+        fn grant_channel_role(
+            &mut self,
+            channel: ChannelId,
+            role: RoleType,
+            account: Address,
+        ) -> Result<(), RollupClientError> {
+            self.inner_grant_channel_role(channel, role, account)
+        }
+
+        #[ink(message)]
+        fn revoke_channel_role(
+            &mut self,
+            channel: ChannelId,
+            role: RoleType,
+            account: Address,
+        ) -> Result<(), RollupClientError> {
+            self.inner_revoke_channel_role(channel, role, account)
+        }
+    }
+
     /// Boilerplate code to implement the Meta Transaction
     impl MetaTransactionStorage for InkClient {
         fn get_storage(&self) -> &MetaTransactionData {
@@ -155,5 +784,14 @@ pub mod test_contract {
         ) -> Result<(), RollupClientError> {
             self.inner_meta_tx_rollup_cond_eq(request, signature)
         }
+
+        #[ink(message)]
+        fn meta_tx_channel_rollup_cond_eq(
+            &mut self,
+            request: ForwardRequest,
+            signature: [u8; 65],
+        ) -> Result<(), RollupClientError> {
+            self.inner_meta_tx_channel_rollup_cond_eq(request, signature)
+        }
     }
 }
@@ -0,0 +1,212 @@
+mod contract;
+mod test_utils;
+
+use contract::test_contract::InkClient;
+use ink::scale::Encode;
+use inkv6_client_lib::traits::access_control::{AccessControl, AccessControlError};
+use inkv6_client_lib::traits::channel::DEFAULT_CHANNEL;
+use inkv6_client_lib::traits::dead_letter_queue::DeadLetterQueue;
+use inkv6_client_lib::traits::kv_store::KvStore;
+use inkv6_client_lib::traits::message_queue::{MessageQueue, QueueBounds, QueueIndex};
+use inkv6_client_lib::traits::rollup_client::*;
+use inkv6_client_lib::traits::RollupClientError;
+use test_utils::{accounts, change_caller};
+
+#[ink::test]
+fn test_failing_reply_is_dead_lettered() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    // the contract rejects this reply
+    let rejected = 1u8.encode();
+    contract
+        .inner_set_value(&b"app/rejected".to_vec(), Some(&rejected))
+        .unwrap();
+
+    contract.push_message(&10u8).unwrap();
+    contract.push_message(&11u8).unwrap();
+
+    // the rest of the batch is applied
+    let actions = vec![
+        HandleActionInput::Reply(rejected.clone()),
+        HandleActionInput::ReplyTo {
+            request_id: 1,
+            payload: 2u8.encode(),
+        },
+        HandleActionInput::SetQueueHead(2),
+    ];
+    assert_eq!(
+        Ok(()),
+        contract.rollup_cond_eq(
+            vec![],
+            vec![(b"key".to_vec(), Some(b"value".to_vec()))],
+            actions
+        )
+    );
+    assert_eq!(Some(b"value".to_vec()), contract.get_value(b"key".to_vec()));
+    assert_eq!(2, contract.get_queue_bounds().unwrap().head);
+
+    // the rejected reply is saved with the error
+    let letters = contract.get_dead_letters(DEFAULT_CHANNEL, 0, 10);
+    assert_eq!(1, letters.len());
+    let (id, letter) = &letters[0];
+    assert_eq!(0, *id);
+    assert_eq!(None, letter.request_id);
+    assert_eq!(None, letter.request);
+    assert_eq!(rejected, letter.payload);
+    assert_eq!(RollupClientError::BusinessError(1), letter.error);
+}
+
+#[ink::test]
+fn test_retry_and_discard_dead_letters() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    let rejected = 1u8.encode();
+    contract
+        .inner_set_value(&b"app/rejected".to_vec(), Some(&rejected))
+        .unwrap();
+
+    let actions = vec![
+        HandleActionInput::Reply(rejected.clone()),
+        HandleActionInput::Reply(rejected.clone()),
+    ];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(2, contract.get_dead_letters(DEFAULT_CHANNEL, 0, 10).len());
+
+    // only the admin can retry or discard the dead letters
+    change_caller(accounts.bob);
+    assert_eq!(
+        Err(RollupClientError::AccessControlError(
+            AccessControlError::MissingRole
+        )),
+        contract.retry_dead_letter(DEFAULT_CHANNEL, 0)
+    );
+    assert_eq!(
+        Err(RollupClientError::AccessControlError(
+            AccessControlError::MissingRole
+        )),
+        contract.discard_dead_letter(DEFAULT_CHANNEL, 0)
+    );
+
+    // the reply is still rejected, the dead letter is kept
+    change_caller(accounts.alice);
+    assert_eq!(
+        Err(RollupClientError::BusinessError(1)),
+        contract.retry_dead_letter(DEFAULT_CHANNEL, 0)
+    );
+    assert_eq!(2, contract.get_dead_letters(DEFAULT_CHANNEL, 0, 10).len());
+
+    // the contract now accepts the reply
+    contract
+        .inner_set_value(&b"app/rejected".to_vec(), None)
+        .unwrap();
+    assert_eq!(Ok(()), contract.retry_dead_letter(DEFAULT_CHANNEL, 0));
+    assert_eq!(Ok(()), contract.discard_dead_letter(DEFAULT_CHANNEL, 1));
+    assert_eq!(0, contract.get_dead_letters(DEFAULT_CHANNEL, 0, 10).len());
+
+    assert_eq!(
+        Err(RollupClientError::DeadLetterNotFound),
+        contract.retry_dead_letter(DEFAULT_CHANNEL, 0)
+    );
+    assert_eq!(
+        Err(RollupClientError::DeadLetterNotFound),
+        contract.discard_dead_letter(DEFAULT_CHANNEL, 1)
+    );
+}
+
+#[ink::test]
+fn test_retry_dead_letter_after_the_request_is_processed() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    let rejected = 1u8.encode();
+    contract
+        .inner_set_value(&b"app/rejected".to_vec(), Some(&rejected))
+        .unwrap();
+    contract.push_message(&10u8).unwrap();
+
+    // the reply is rejected and the request is processed in the same batch
+    let actions = vec![
+        HandleActionInput::ReplyTo {
+            request_id: 0,
+            payload: rejected.clone(),
+        },
+        HandleActionInput::SetQueueHead(1),
+    ];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    let letters = contract.get_dead_letters(DEFAULT_CHANNEL, 0, 10);
+    assert_eq!(Some(0), letters[0].1.request_id);
+    assert_eq!(Some(10u8.encode()), letters[0].1.request);
+    assert_eq!(vec![accounts.alice], letters[0].1.attestors);
+    assert_eq!(Ok(false), MessageQueue::has_message(&contract));
+
+    // the reply is handled with the request saved in the dead letter
+    contract
+        .inner_set_value(&b"app/rejected".to_vec(), None)
+        .unwrap();
+    assert_eq!(Ok(()), contract.retry_dead_letter(DEFAULT_CHANNEL, 0));
+    assert_eq!(
+        Some((10u8.encode(), rejected).encode()),
+        contract.inner_get_value(&b"app/reply".to_vec())
+    );
+    assert_eq!(0, contract.get_dead_letters(DEFAULT_CHANNEL, 0, 10).len());
+}
+
+#[ink::test]
+fn test_retry_dead_letter_after_the_epoch_changes() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    let rejected = 1u8.encode();
+    contract
+        .inner_set_value(&b"app/rejected".to_vec(), Some(&rejected))
+        .unwrap();
+    contract.push_message(&10u8).unwrap();
+
+    let first_queue = contract.queue_id();
+    let actions = vec![
+        HandleActionInput::ReplyTo {
+            request_id: 0,
+            payload: rejected.clone(),
+        },
+        HandleActionInput::SetQueueHead(1),
+    ];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    let letters = contract.get_dead_letters(DEFAULT_CHANNEL, 0, 10);
+    assert_eq!(first_queue, letters[0].1.queue);
+
+    // the queue reaches the max index, the indexes restart from 0 in a new epoch
+    let bounds = QueueBounds {
+        head: QueueIndex::MAX,
+        tail: QueueIndex::MAX,
+        pruned_to: QueueIndex::MAX,
+    };
+    contract.set_queue_bounds_of(first_queue, &bounds);
+    assert_eq!(Ok(0), contract.push_message(&20u8));
+    assert_ne!(first_queue, contract.queue_id());
+
+    // the reply is handled with its request, the new message with the same index is not answered
+    contract
+        .inner_set_value(&b"app/rejected".to_vec(), None)
+        .unwrap();
+    assert_eq!(Ok(()), contract.retry_dead_letter(DEFAULT_CHANNEL, 0));
+    assert_eq!(
+        Some((10u8.encode(), rejected).encode()),
+        contract.inner_get_value(&b"app/reply".to_vec())
+    );
+    assert_eq!(None, contract.get_message_status(0));
+    assert_eq!(Ok(Some(20u8)), MessageQueue::get_message(&contract, 0));
+}
@@ -0,0 +1,90 @@
+mod contract;
+mod test_utils;
+
+use contract::test_contract::InkClient;
+use inkv6_client_lib::traits::access_control::{AccessControl, AccessControlError};
+use inkv6_client_lib::traits::encrypted_payload::{
+    BaseEncryptedMessageQueue, EncryptedMessageQueue, EncryptedPayload, WorkerPublicKey,
+};
+use inkv6_client_lib::traits::message_queue::{MessageQueue, PushOptions};
+use inkv6_client_lib::traits::rollup_client::*;
+use inkv6_client_lib::traits::RollupClientError;
+use test_utils::{accounts, change_caller};
+
+fn encrypted_payload(key_id: u32) -> EncryptedPayload {
+    EncryptedPayload {
+        key_id,
+        ephemeral_public_key: [1; 32],
+        nonce: [2; 12],
+        ciphertext: vec![3; 48],
+    }
+}
+
+#[ink::test]
+fn test_worker_public_key() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.bob));
+    assert_eq!(None, contract.get_worker_public_key());
+
+    // only an attestor can register the key
+    assert_eq!(
+        Err(RollupClientError::AccessControlError(
+            AccessControlError::MissingRole
+        )),
+        contract.set_worker_public_key([7; 32])
+    );
+
+    change_caller(accounts.bob);
+    assert_eq!(Ok(()), contract.set_worker_public_key([7; 32]));
+    assert_eq!(
+        Some(WorkerPublicKey {
+            id: 0,
+            public_key: [7; 32]
+        }),
+        contract.get_worker_public_key()
+    );
+
+    assert_eq!(Ok(()), contract.set_worker_public_key([8; 32]));
+    assert_eq!(
+        Some(WorkerPublicKey {
+            id: 1,
+            public_key: [8; 32]
+        }),
+        contract.get_worker_public_key()
+    );
+}
+
+#[ink::test]
+fn test_push_encrypted_message() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    // no key registered
+    assert_eq!(
+        Err(RollupClientError::UnknownEncryptionKey),
+        contract.push_encrypted_message(&encrypted_payload(0), PushOptions::default())
+    );
+
+    assert_eq!(Ok(()), contract.set_worker_public_key([7; 32]));
+    assert_eq!(Ok(()), contract.set_worker_public_key([8; 32]));
+
+    // the payloads encrypted with the previous key are rejected
+    assert_eq!(
+        Err(RollupClientError::UnknownEncryptionKey),
+        contract.push_encrypted_message(&encrypted_payload(0), PushOptions::default())
+    );
+
+    let payload = encrypted_payload(1);
+    assert_eq!(
+        Ok(0),
+        contract.push_encrypted_message(&payload, PushOptions::default())
+    );
+    assert_eq!(Ok(Some(payload)), MessageQueue::get_message(&contract, 0));
+    assert_eq!(1, contract.get_queue_bounds().unwrap().tail);
+}
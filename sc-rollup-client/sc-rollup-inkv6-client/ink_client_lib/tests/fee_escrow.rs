@@ -0,0 +1,310 @@
+mod contract;
+mod test_utils;
+
+use contract::test_contract::InkClient;
+use ink::env::DefaultEnvironment;
+use ink::scale::Encode;
+use ink::{Address, U256};
+use inkv6_client_lib::traits::access_control::{AccessControl, AccessControlError};
+use inkv6_client_lib::traits::fee_escrow::{BaseFeeEscrow, Escrow, FeeEscrow, FeeTarget};
+use inkv6_client_lib::traits::kv_store::KvStore;
+use inkv6_client_lib::traits::message_queue::{
+    PushOptions, DEFAULT_TOPIC, MAX_PRUNED_MESSAGES_ON_POP,
+};
+use inkv6_client_lib::traits::reply_quorum::{QuorumConfig, ReplyQuorum};
+use inkv6_client_lib::traits::rollup_client::*;
+use inkv6_client_lib::traits::RollupClientError;
+use test_utils::{accounts, change_caller};
+
+fn set_transferred_value(value: u128) {
+    ink::env::test::set_value_transferred(U256::from(value));
+}
+
+fn balance_of(account: Address) -> U256 {
+    ink::env::test::get_account_balance::<DefaultEnvironment>(account).unwrap_or_default()
+}
+
+fn reply_to(
+    contract: &mut InkClient,
+    attestor: Address,
+    request_id: u32,
+    payload: u8,
+) -> Result<(), RollupClientError> {
+    change_caller(attestor);
+    let actions = vec![HandleActionInput::ReplyTo {
+        request_id,
+        payload: payload.encode(),
+    }];
+    contract.rollup_cond_eq(vec![], vec![], actions)
+}
+
+#[ink::test]
+fn test_fee_schedule() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+
+    // only the admin can set the fees
+    change_caller(accounts.bob);
+    assert_eq!(
+        Err(RollupClientError::AccessControlError(
+            AccessControlError::MissingRole
+        )),
+        contract.set_fee(FeeTarget::Topic(DEFAULT_TOPIC), Some(U256::from(10)))
+    );
+
+    change_caller(accounts.alice);
+    assert_eq!(
+        Ok(()),
+        contract.set_fee(FeeTarget::Topic(DEFAULT_TOPIC), Some(U256::from(10)))
+    );
+    assert_eq!(
+        Ok(()),
+        contract.set_fee(FeeTarget::TypeTag(1), Some(U256::from(50)))
+    );
+    assert_eq!(
+        Some(U256::from(10)),
+        contract.get_fee(FeeTarget::Topic(DEFAULT_TOPIC))
+    );
+
+    // the fee of the type takes precedence over the fee of the topic
+    change_caller(accounts.bob);
+    set_transferred_value(20);
+    let options = PushOptions {
+        type_tag: Some(1),
+        ..Default::default()
+    };
+    assert_eq!(
+        Err(RollupClientError::InsufficientFee),
+        contract.push_paid_message(&1u8, options.clone())
+    );
+    assert_eq!(
+        Ok(0),
+        contract.push_paid_message(&2u8, PushOptions::default())
+    );
+
+    set_transferred_value(5);
+    assert_eq!(
+        Err(RollupClientError::InsufficientFee),
+        contract.push_paid_message(&3u8, PushOptions::default())
+    );
+
+    set_transferred_value(50);
+    assert_eq!(Ok(1), contract.push_paid_message(&4u8, options));
+
+    // the whole transferred value is kept with the message
+    assert_eq!(
+        Some(Escrow {
+            payer: accounts.bob,
+            amount: U256::from(20)
+        }),
+        contract.get_escrow(0)
+    );
+    assert_eq!(
+        Some(Escrow {
+            payer: accounts.bob,
+            amount: U256::from(50)
+        }),
+        contract.get_escrow(1)
+    );
+
+    // no fee, no escrow
+    change_caller(accounts.alice);
+    assert_eq!(
+        Ok(()),
+        contract.set_fee(FeeTarget::Topic(DEFAULT_TOPIC), None)
+    );
+    set_transferred_value(0);
+    assert_eq!(
+        Ok(2),
+        contract.push_paid_message(&5u8, PushOptions::default())
+    );
+    assert_eq!(None, contract.get_escrow(2));
+}
+
+#[ink::test]
+fn test_fee_released_or_refunded() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+    ink::env::test::set_contract_balance(ink::env::address(), U256::from(1_000));
+
+    change_caller(accounts.bob);
+    set_transferred_value(10);
+    for i in 0..5u8 {
+        contract
+            .push_paid_message(&i, PushOptions::default())
+            .unwrap();
+    }
+    set_transferred_value(0);
+
+    change_caller(accounts.alice);
+    contract.set_payout_address(Some(accounts.django));
+    let django_balance = balance_of(accounts.django);
+    let bob_balance = balance_of(accounts.bob);
+
+    // the reply rejected by the contract (kept in the dead letter queue) is not paid
+    contract
+        .inner_set_value(&b"app/rejected".to_vec(), Some(&9u8.encode()))
+        .unwrap();
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.alice, 0, 9));
+    assert!(contract.get_escrow(0).is_some());
+    assert_eq!(django_balance, balance_of(accounts.django));
+
+    // the fee is paid to the payout address of the attestor when the reply is accepted
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.alice, 0, 1));
+    assert_eq!(None, contract.get_escrow(0));
+    assert_eq!(django_balance + 10, balance_of(accounts.django));
+
+    // the fee is paid to the attestor who acknowledges the message
+    let actions = vec![HandleActionInput::Ack(vec![1])];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(None, contract.get_escrow(1));
+    assert_eq!(django_balance + 20, balance_of(accounts.django));
+
+    // the fee is refunded to the sender when the message is cancelled
+    change_caller(accounts.bob);
+    assert!(contract.get_escrow(2).is_some());
+    assert_eq!(Ok(()), contract.cancel(2));
+    assert_eq!(None, contract.get_escrow(2));
+    assert_eq!(bob_balance + 10, balance_of(accounts.bob));
+
+    // the fee is paid to the attestor who consumes the message with the action `SetQueueHead`
+    // (when the message is deleted from the queue)
+    change_caller(accounts.alice);
+    let actions = vec![
+        HandleActionInput::SetQueueHead(4),
+        HandleActionInput::Reply(3u8.encode()),
+    ];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(None, contract.get_escrow(3));
+    assert_eq!(django_balance + 30, balance_of(accounts.django));
+    assert!(contract.get_escrow(4).is_some());
+
+    assert_eq!(bob_balance + 10, balance_of(accounts.bob));
+    assert_eq!(U256::from(1_000 - 40), balance_of(ink::env::address()));
+}
+
+#[ink::test]
+fn test_fee_paid_to_the_consumer_when_the_message_is_pruned() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.charlie));
+    ink::env::test::set_contract_balance(ink::env::address(), U256::from(1_000));
+
+    // more messages than the ones deleted when the head is moved
+    let nb_messages = MAX_PRUNED_MESSAGES_ON_POP + 2;
+    change_caller(accounts.bob);
+    set_transferred_value(10);
+    for i in 0..nb_messages {
+        contract
+            .push_paid_message(&i, PushOptions::default())
+            .unwrap();
+    }
+    set_transferred_value(0);
+    let balances = [accounts.alice, accounts.bob, accounts.charlie].map(balance_of);
+
+    // alice consumes the first messages, only a few are deleted now
+    change_caller(accounts.alice);
+    let actions = vec![HandleActionInput::SetQueueHead(nb_messages - 1)];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(
+        balances[0] + 10 * MAX_PRUNED_MESSAGES_ON_POP,
+        balance_of(accounts.alice)
+    );
+
+    // the fees of the other messages are paid to alice when they are deleted
+    assert_eq!(Ok(0), contract.prune_queue(10));
+    assert_eq!(
+        balances[0] + 10 * (nb_messages - 1),
+        balance_of(accounts.alice)
+    );
+
+    // charlie consumes the last message
+    change_caller(accounts.charlie);
+    let actions = vec![HandleActionInput::SetQueueHead(nb_messages)];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(balances[2] + 10, balance_of(accounts.charlie));
+    assert_eq!(balances[1], balance_of(accounts.bob));
+}
+
+#[ink::test]
+fn test_fee_claimed_when_the_transfer_fails() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+    ink::env::test::set_contract_balance(ink::env::address(), U256::zero());
+
+    change_caller(accounts.bob);
+    set_transferred_value(10);
+    contract
+        .push_paid_message(&1u8, PushOptions::default())
+        .unwrap();
+    set_transferred_value(0);
+    let balance = balance_of(accounts.alice);
+
+    // the transfer fails but the reply is accepted and the fee is kept for the attestor
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.alice, 0, 1));
+    assert_eq!(None, contract.get_escrow(0));
+    assert_eq!(U256::from(10), contract.get_claimable_fees(accounts.alice));
+    assert_eq!(balance, balance_of(accounts.alice));
+
+    // the transfer still fails
+    change_caller(accounts.alice);
+    assert_eq!(
+        Err(RollupClientError::TransferFailed),
+        contract.claim_fees()
+    );
+    assert_eq!(U256::from(10), contract.get_claimable_fees(accounts.alice));
+
+    // the attestor claims the fee later
+    ink::env::test::set_contract_balance(ink::env::address(), U256::from(1_000));
+    assert_eq!(Ok(U256::from(10)), contract.claim_fees());
+    assert_eq!(U256::zero(), contract.get_claimable_fees(accounts.alice));
+    assert_eq!(balance + 10, balance_of(accounts.alice));
+    assert_eq!(Ok(U256::zero()), contract.claim_fees());
+}
+
+#[ink::test]
+fn test_fee_shared_by_the_attestors() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    for attestor in [accounts.alice, accounts.bob, accounts.charlie] {
+        assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, attestor));
+    }
+    let config = QuorumConfig {
+        quorum: 3,
+        timeout: 10,
+    };
+    assert_eq!(Ok(()), contract.set_quorum_config(config));
+    ink::env::test::set_contract_balance(ink::env::address(), U256::from(1_000));
+
+    change_caller(accounts.eve);
+    set_transferred_value(11);
+    assert_eq!(
+        Ok(0),
+        contract.push_paid_message(&1u8, PushOptions::default())
+    );
+    set_transferred_value(0);
+
+    let balances = [accounts.alice, accounts.bob, accounts.charlie].map(balance_of);
+
+    // the fee is shared by the attestors who have sent the reply handled, the remainder goes to the first one
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.alice, 0, 1));
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.bob, 0, 1));
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.charlie, 0, 2));
+    assert_eq!(None, contract.get_escrow(0));
+    assert_eq!(balances[0] + 6, balance_of(accounts.alice));
+    assert_eq!(balances[1] + 5, balance_of(accounts.bob));
+    assert_eq!(balances[2], balance_of(accounts.charlie));
+}
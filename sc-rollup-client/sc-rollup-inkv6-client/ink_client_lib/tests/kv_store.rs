@@ -5,8 +5,11 @@ use ink::scale::Encode;
 use inkv6_client_lib::traits::rollup_client::*;
 
 use contract::test_contract::InkClient;
-use inkv6_client_lib::traits::kv_store::KvStore;
-use test_utils::accounts;
+use inkv6_client_lib::traits::access_control::AccessControlError;
+use inkv6_client_lib::traits::kv_store::{KvStore, KvStoreLimits, KvStoreQuota, KvStoreUsage};
+use inkv6_client_lib::traits::message_queue::MessageQueue;
+use inkv6_client_lib::traits::RollupClientError;
+use test_utils::{accounts, change_caller};
 
 #[ink::test]
 fn test_get_no_value() {
@@ -24,11 +27,15 @@ fn test_set_encoded_values() {
 
     let key_1 = b"0x123".to_vec();
     let some_value_1 = "0x456".encode();
-    contract.inner_set_value(&key_1, Some(&some_value_1));
+    contract
+        .inner_set_value(&key_1, Some(&some_value_1))
+        .unwrap();
 
     let key_2 = b"0x124".to_vec();
     let some_value_2 = "0x457".encode();
-    contract.inner_set_value(&key_2, Some(&some_value_2));
+    contract
+        .inner_set_value(&key_2, Some(&some_value_2))
+        .unwrap();
 
     match contract.get_value(key_1.clone()) {
         Some(v) => assert_eq!(some_value_1, v),
@@ -55,7 +62,7 @@ fn test_update_same_key() {
 
     // update the value
     let some_value = "0x456".encode();
-    contract.inner_set_value(&key, Some(&some_value));
+    contract.inner_set_value(&key, Some(&some_value)).unwrap();
 
     assert_eq!(
         contract.inner_get_value(&key),
@@ -65,7 +72,9 @@ fn test_update_same_key() {
 
     // update the value
     let another_value = "0x457".encode();
-    contract.inner_set_value(&key, Some(&another_value));
+    contract
+        .inner_set_value(&key, Some(&another_value))
+        .unwrap();
     assert_eq!(
         contract.inner_get_value(&key),
         Some(another_value),
@@ -73,7 +82,7 @@ fn test_update_same_key() {
     );
 
     // remove the value
-    contract.inner_set_value(&key, None);
+    contract.inner_set_value(&key, None).unwrap();
     assert_eq!(
         contract.inner_get_value(&key),
         None,
@@ -82,10 +91,143 @@ fn test_update_same_key() {
 
     // update the value
     let another_value = "0x458".encode();
-    contract.inner_set_value(&key, Some(&another_value));
+    contract
+        .inner_set_value(&key, Some(&another_value))
+        .unwrap();
     assert_eq!(
         contract.inner_get_value(&key),
         Some(another_value),
         "We should find a value for the key {key:?}"
     );
 }
+
+#[ink::test]
+fn test_kv_store_limits() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+    let mut contract = InkClient::new(accounts.alice);
+
+    assert_eq!(KvStoreLimits::default(), contract.get_kv_store_limits());
+
+    let limits = KvStoreLimits {
+        max_key_size: Some(4),
+        max_value_size: Some(8),
+        max_total_size: Some(20),
+    };
+
+    // only the admin can set the limits
+    change_caller(accounts.bob);
+    assert_eq!(
+        Err(RollupClientError::AccessControlError(
+            AccessControlError::MissingRole
+        )),
+        contract.set_kv_store_limits(limits.clone())
+    );
+    change_caller(accounts.alice);
+    assert_eq!(Ok(()), contract.set_kv_store_limits(limits.clone()));
+    assert_eq!(limits, contract.get_kv_store_limits());
+
+    // key too large
+    assert_eq!(
+        Err(RollupClientError::KeyTooLarge),
+        contract.inner_set_value(&b"12345".to_vec(), Some(&vec![0u8]))
+    );
+    // value too large
+    assert_eq!(
+        Err(RollupClientError::ValueTooLarge),
+        contract.inner_set_value(&b"1234".to_vec(), Some(&vec![0u8; 9]))
+    );
+    // 4 bytes for the key + 9 bytes for the encoded value
+    assert_eq!(
+        Ok(()),
+        contract.inner_set_value(&b"1234".to_vec(), Some(&vec![0u8; 8]))
+    );
+    // the quota is exceeded
+    assert_eq!(
+        Err(RollupClientError::StorageQuotaExceeded),
+        contract.inner_set_value(&b"5678".to_vec(), Some(&vec![0u8; 8]))
+    );
+    // but we can still reduce the size of a value or remove it
+    assert_eq!(
+        Ok(()),
+        contract.inner_set_value(&b"1234".to_vec(), Some(&vec![0u8; 2]))
+    );
+    assert_eq!(
+        Ok(()),
+        contract.inner_set_value(&b"5678".to_vec(), Some(&vec![0u8; 2]))
+    );
+    assert_eq!(Ok(()), contract.inner_set_value(&b"1234".to_vec(), None));
+}
+
+#[ink::test]
+fn test_kv_store_usage() {
+    let accounts = accounts();
+    let mut contract = InkClient::new(accounts.alice);
+
+    let report = contract.kv_store_usage();
+    assert_eq!(KvStoreUsage::default(), report.total);
+
+    // 3 bytes for the key + 4 bytes for the encoded value
+    contract
+        .inner_set_value(&b"abc".to_vec(), Some(&vec![1u8, 2, 3]))
+        .unwrap();
+    // 6 bytes for the key + 2 bytes for the encoded value
+    contract
+        .inner_set_value(&b"app/xy".to_vec(), Some(&vec![1u8]))
+        .unwrap();
+    // the message queue is not saved in the key value store
+    contract.push_message(&1u16).unwrap();
+
+    let report = contract.kv_store_usage();
+    let get_usage = |prefix: &[u8]| {
+        report
+            .by_prefix
+            .iter()
+            .find(|(p, _)| p.as_slice() == prefix)
+            .map(|(_, usage)| usage.clone())
+            .expect("usage not found")
+    };
+    assert_eq!(
+        KvStoreUsage {
+            nb_keys: 1,
+            nb_bytes: 7
+        },
+        get_usage(b"")
+    );
+    assert_eq!(
+        KvStoreUsage {
+            nb_keys: 1,
+            nb_bytes: 8
+        },
+        get_usage(b"app/")
+    );
+    assert!(report.by_prefix.iter().all(|(p, _)| p.as_slice() != b"q/"));
+    assert_eq!(2, report.total.nb_keys);
+
+    // update and remove the values
+    contract
+        .inner_set_value(&b"abc".to_vec(), Some(&vec![1u8]))
+        .unwrap();
+    contract.inner_set_value(&b"app/xy".to_vec(), None).unwrap();
+    // remove a missing value
+    contract.inner_set_value(&b"xyz".to_vec(), None).unwrap();
+
+    let report = contract.kv_store_usage();
+    let get_usage = |prefix: &[u8]| {
+        report
+            .by_prefix
+            .iter()
+            .find(|(p, _)| p.as_slice() == prefix)
+            .map(|(_, usage)| usage.clone())
+            .expect("usage not found")
+    };
+    assert_eq!(
+        KvStoreUsage {
+            nb_keys: 1,
+            nb_bytes: 5
+        },
+        get_usage(b"")
+    );
+    assert_eq!(KvStoreUsage::default(), get_usage(b"app/"));
+    assert_eq!(1, report.total.nb_keys);
+}
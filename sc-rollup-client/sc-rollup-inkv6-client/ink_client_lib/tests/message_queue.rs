@@ -2,9 +2,18 @@ mod contract;
 mod test_utils;
 
 use contract::test_contract::InkClient;
-use inkv6_client_lib::traits::message_queue::MessageQueue;
+use ink::env::DefaultEnvironment;
+use ink::scale::{Decode, Encode};
+use inkv6_client_lib::traits::access_control::AccessControlError;
+use inkv6_client_lib::traits::channel::DEFAULT_CHANNEL;
+use inkv6_client_lib::traits::kv_store::KvStore;
+use inkv6_client_lib::traits::message_queue::{
+    MessageQueue, MessageQueueMigration, MessageQueueQuota, MessageQueueStorage, MessageStatus,
+    QueueBounds, QueueIndex, QueueLimits, QueueStats, TopicQueue, DEFAULT_TOPIC,
+    MAX_PRUNED_MESSAGES_ON_POP, MAX_SKIPPED_MESSAGES_ON_POP,
+};
 use inkv6_client_lib::traits::RollupClientError;
-use test_utils::accounts;
+use test_utils::{accounts, change_caller};
 
 #[ink::test]
 fn test_push_and_pop_message() {
@@ -90,3 +99,500 @@ fn test_pop_messages() {
     assert_eq!(5, contract.get_queue_head().unwrap());
     assert_eq!(5, contract.get_queue_tail().unwrap());
 }
+
+#[ink::test]
+fn test_pop_and_prune_messages() {
+    let accounts = accounts();
+    let mut contract = InkClient::new(accounts.alice);
+
+    for i in 0..50u128 {
+        contract.push_message(&i).unwrap();
+    }
+
+    let is_saved = |contract: &InkClient, id: QueueIndex| {
+        MessageQueueStorage::get_storage(contract)
+            .messages
+            .contains((contract.queue_id(), id))
+    };
+
+    // the head is moved but only a few messages are deleted
+    assert_eq!(Ok(()), contract.pop_to(45));
+    assert_eq!(45, contract.get_queue_head().unwrap());
+    assert_eq!(false, is_saved(&contract, MAX_PRUNED_MESSAGES_ON_POP - 1));
+    assert_eq!(true, is_saved(&contract, MAX_PRUNED_MESSAGES_ON_POP));
+    assert_eq!(true, is_saved(&contract, 44));
+
+    // the processed messages are unreadable
+    assert_eq!(None, contract.get_message::<u128>(44).unwrap());
+    let key = [b"q/".as_slice(), &44u32.encode()].concat();
+    assert_eq!(None, contract.inner_get_rollup_value(&key));
+    assert_eq!(Some(45u128), contract.get_message(45).unwrap());
+
+    // prune the processed messages
+    assert_eq!(Ok(25), contract.prune_queue(10));
+    assert_eq!(false, is_saved(&contract, 19));
+    assert_eq!(true, is_saved(&contract, 20));
+    assert_eq!(Ok(0), contract.prune_queue(100));
+    assert_eq!(false, is_saved(&contract, 44));
+    assert_eq!(true, is_saved(&contract, 45));
+    assert_eq!(Ok(0), contract.prune_queue(100));
+
+    assert_eq!(45, contract.get_queue_head().unwrap());
+    assert_eq!(50, contract.get_queue_tail().unwrap());
+}
+
+#[ink::test]
+fn test_ack_messages() {
+    let accounts = accounts();
+    let mut contract = InkClient::new(accounts.alice);
+
+    for i in 0..6u128 {
+        contract.push_message(&i).unwrap();
+    }
+
+    // the messages are acknowledged out of order, the head is not moved
+    assert_eq!(Ok(()), contract.ack_message(2));
+    assert_eq!(Ok(()), contract.ack_message(3));
+    assert_eq!(Ok(()), contract.ack_message(5));
+    assert_eq!(0, contract.get_queue_head().unwrap());
+    assert_eq!(
+        Some(MessageStatus::Acknowledged),
+        contract.get_message_status(2)
+    );
+
+    // the acknowledged messages are no longer pending
+    assert_eq!(None, contract.get_message::<u128>(2).unwrap());
+    let key = [b"q/".as_slice(), &2u32.encode()].concat();
+    assert_eq!(None, contract.inner_get_rollup_value(&key));
+    assert_eq!(Some(1u128), contract.get_message(1).unwrap());
+    assert_eq!(
+        Err(RollupClientError::MessageNotPending),
+        contract.ack_message(2)
+    );
+
+    // the head is moved after the acknowledged messages
+    assert_eq!(Ok(()), contract.ack_message(0));
+    assert_eq!(1, contract.get_queue_head().unwrap());
+    assert_eq!(Ok(()), contract.ack_message(1));
+    assert_eq!(4, contract.get_queue_head().unwrap());
+    assert_eq!(Ok(()), contract.ack_message(4));
+    assert_eq!(6, contract.get_queue_head().unwrap());
+    assert_eq!(false, contract.has_message().unwrap());
+
+    // the processed messages and the messages not pushed yet can't be acknowledged
+    assert_eq!(
+        Err(RollupClientError::MessageNotPending),
+        contract.ack_message(0)
+    );
+    assert_eq!(
+        Err(RollupClientError::MessageNotPending),
+        contract.ack_message(6)
+    );
+
+    // the status is deleted with the message
+    assert_eq!(Ok(0), contract.prune_queue(10));
+    assert_eq!(None, contract.get_message_status(2));
+}
+
+#[ink::test]
+fn test_ack_many_messages() {
+    let accounts = accounts();
+    let mut contract = InkClient::new(accounts.alice);
+
+    let last = MAX_SKIPPED_MESSAGES_ON_POP + 2;
+    for i in 0..=last {
+        contract.push_message(&i).unwrap();
+    }
+    for i in 1..last {
+        assert_eq!(Ok(()), contract.ack_message(i));
+    }
+
+    // a limited number of acknowledged messages are skipped when the head is acknowledged
+    assert_eq!(Ok(()), contract.ack_message(0));
+    assert_eq!(
+        MAX_SKIPPED_MESSAGES_ON_POP + 1,
+        contract.get_queue_head().unwrap()
+    );
+
+    // the next ones are skipped by the next acknowledgement
+    assert_eq!(Ok(()), contract.ack_message(last));
+    assert_eq!(last + 1, contract.get_queue_head().unwrap());
+}
+
+#[ink::test]
+fn test_push_message_dedup() {
+    let accounts = accounts();
+    let mut contract = InkClient::new(accounts.alice);
+
+    let key1 = b"request-1".to_vec();
+    let key2 = b"request-2".to_vec();
+
+    // the same request is pushed only once
+    assert_eq!(Ok(0), contract.push_message_dedup(&key1, &1u8));
+    assert_eq!(Ok(0), contract.push_message_dedup(&key1, &1u8));
+    assert_eq!(Ok(1), contract.push_message_dedup(&key2, &2u8));
+    assert_eq!(Ok(2), contract.push_message(&3u8));
+    assert_eq!(3, contract.get_queue_tail().unwrap());
+
+    // the request can be pushed again once the message is processed
+    assert_eq!(Ok(()), contract.pop_to(1));
+    assert_eq!(Ok(3), contract.push_message_dedup(&key1, &1u8));
+    assert_eq!(Ok(1), contract.push_message_dedup(&key2, &2u8));
+
+    // the table is cleaned when the messages are deleted
+    let queue = contract.queue_id();
+    let storage = MessageQueueStorage::get_storage(&contract);
+    assert_eq!(None, storage.dedup_keys.get((queue, 0)));
+    assert_eq!(Some(3), storage.dedup.get((queue, &key1)));
+    assert_eq!(Ok(()), contract.pop_to(4));
+    let storage = MessageQueueStorage::get_storage(&contract);
+    assert_eq!(None, storage.dedup.get((queue, &key1)));
+    assert_eq!(None, storage.dedup.get((queue, &key2)));
+}
+
+#[ink::test]
+fn test_queue_limits_and_stats() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+    let mut contract = InkClient::new(accounts.alice);
+
+    assert_eq!(Ok(QueueStats::default()), contract.queue_stats());
+
+    let limits = QueueLimits {
+        max_pending_messages: Some(2),
+        ..Default::default()
+    };
+
+    // only the admin can set the limits
+    change_caller(accounts.bob);
+    assert_eq!(
+        Err(RollupClientError::AccessControlError(
+            AccessControlError::MissingRole
+        )),
+        contract.set_queue_limits(limits.clone())
+    );
+    change_caller(accounts.alice);
+    assert_eq!(Ok(()), contract.set_queue_limits(limits.clone()));
+    assert_eq!(limits, contract.get_queue_limits());
+
+    let block = ink::env::block_number::<DefaultEnvironment>();
+    assert_eq!(Ok(0), contract.push_message(&1u8));
+    ink::env::test::advance_block::<DefaultEnvironment>();
+    assert_eq!(Ok(1), contract.push_message(&2u8));
+    assert_eq!(
+        Err(RollupClientError::QueueFull),
+        contract.push_message(&3u8)
+    );
+
+    // the other topics have their own queue
+    assert_eq!(Ok(0), contract.push_message_to(1, &3u8));
+
+    assert_eq!(
+        Ok(QueueStats {
+            pending_messages: 2,
+            oldest_pending_block: Some(block),
+            total_processed: 0,
+        }),
+        contract.queue_stats()
+    );
+
+    // a message can be pushed again when the workers process the queue
+    assert_eq!(Ok(()), contract.pop_to(1));
+    assert_eq!(Ok(2), contract.push_message(&3u8));
+    assert_eq!(
+        Ok(QueueStats {
+            pending_messages: 2,
+            oldest_pending_block: Some(block + 1),
+            total_processed: 1,
+        }),
+        contract.queue_stats()
+    );
+}
+
+#[ink::test]
+fn test_topics() {
+    let accounts = accounts();
+    let mut contract = InkClient::new(accounts.alice);
+
+    const URGENT: u32 = ink::selector_id!("urgent");
+
+    assert_eq!(Ok(0), contract.push_message(&1u8));
+    assert_eq!(Ok(1), contract.push_message(&2u8));
+
+    // each topic has its own head and tail
+    assert_eq!(Ok(0), contract.push_message_to(URGENT, &10u8));
+    assert_eq!(Ok(1), contract.push_message_to(URGENT, &11u8));
+    assert_eq!(Ok(2), contract.push_message_to(URGENT, &12u8));
+    assert_eq!(Ok(2), contract.push_message_to(DEFAULT_TOPIC, &3u8));
+
+    assert_eq!(3, contract.get_queue_tail().unwrap());
+    assert_eq!(Some(1u8), contract.get_message(0).unwrap());
+
+    let mut urgent = TopicQueue::new(&mut contract, URGENT);
+    assert_eq!(3, urgent.get_queue_tail().unwrap());
+    assert_eq!(Some(10u8), urgent.get_message(0).unwrap());
+
+    // the head of a topic is moved without impacting the other topics
+    assert_eq!(Ok(()), urgent.pop_to(2));
+    assert_eq!(2, urgent.get_queue_head().unwrap());
+    assert_eq!(None, urgent.get_message::<u8>(1).unwrap());
+    assert_eq!(Some(12u8), urgent.get_message(2).unwrap());
+
+    assert_eq!(0, contract.get_queue_head().unwrap());
+    assert_eq!(Some(2u8), contract.get_message(1).unwrap());
+
+    // the topics are not readable with the keys of the default queue
+    let key = [b"q/".as_slice(), &2u32.encode()].concat();
+    assert_eq!(Some(3u8.encode()), contract.inner_get_rollup_value(&key));
+}
+
+#[ink::test]
+fn test_message_envelope() {
+    let accounts = accounts();
+    let mut contract = InkClient::new(accounts.alice);
+
+    change_caller(accounts.bob);
+    ink::env::test::set_block_timestamp::<DefaultEnvironment>(1_000);
+    assert_eq!(Ok(0), contract.push_message(&123u128));
+
+    ink::env::test::advance_block::<DefaultEnvironment>();
+    change_caller(accounts.charlie);
+    assert_eq!(Ok(1), contract.push_message_with_tag(&456u128, Some(7)));
+
+    let envelope = contract.get_message_envelope(0).unwrap().unwrap();
+    assert_eq!(accounts.bob, envelope.sender);
+    assert_eq!(1_000, envelope.timestamp);
+    assert_eq!(None, envelope.type_tag);
+
+    let new_envelope = contract.get_message_envelope(1).unwrap().unwrap();
+    assert_eq!(accounts.charlie, new_envelope.sender);
+    assert_eq!(envelope.block_number + 1, new_envelope.block_number);
+    assert_eq!(Some(7), new_envelope.type_tag);
+
+    // the payload is not changed by the envelope
+    assert_eq!(Some(456u128), contract.get_message(1).unwrap());
+    let key = [b"q/".as_slice(), &1u32.encode()].concat();
+    assert_eq!(
+        Some(456u128.encode()),
+        contract.inner_get_rollup_value(&key)
+    );
+
+    // no envelope for the processed messages
+    assert_eq!(Ok(()), contract.pop_to(1));
+    assert_eq!(Ok(None), contract.get_message_envelope(0));
+    assert_eq!(Ok(None), contract.get_message_envelope(2));
+}
+
+/// Push a message with the previous layout: the queue saved in the key value store
+fn legacy_push_message(contract: &mut InkClient, message: &[u8]) {
+    let tail_key = b"q/_tail".to_vec();
+    let id = contract
+        .inner_get_channel_value(DEFAULT_CHANNEL, &tail_key)
+        .map(|v| QueueIndex::decode(&mut v.as_slice()).unwrap())
+        .unwrap_or_default();
+    let key = [b"q/".as_slice(), &id.encode()].concat();
+    contract
+        .inner_set_value(&key, Some(&message.to_vec()))
+        .unwrap();
+    contract
+        .inner_set_value(&tail_key, Some(&(id + 1).encode()))
+        .unwrap();
+}
+
+/// Pop a message with the previous layout: the queue saved in the key value store
+fn legacy_pop_message(contract: &mut InkClient) {
+    let head_key = b"q/_head".to_vec();
+    let tail_key = b"q/_tail".to_vec();
+    let head = contract
+        .inner_get_channel_value(DEFAULT_CHANNEL, &head_key)
+        .map(|v| QueueIndex::decode(&mut v.as_slice()).unwrap())
+        .unwrap_or_default();
+    let _tail = contract.inner_get_channel_value(DEFAULT_CHANNEL, &tail_key);
+    let key = [b"q/".as_slice(), &head.encode()].concat();
+    contract.inner_set_value(&key, None).unwrap();
+    contract
+        .inner_set_value(&head_key, Some(&(head + 1).encode()))
+        .unwrap();
+}
+
+/// Number of storage reads and writes done by the given function
+fn storage_rw<F: FnOnce()>(f: F) -> (usize, usize) {
+    let address = ink::env::address();
+    let (reads, writes) = ink::env::test::get_contract_storage_rw(address);
+    f();
+    let (new_reads, new_writes) = ink::env::test::get_contract_storage_rw(address);
+    (new_reads - reads, new_writes - writes)
+}
+
+#[ink::test]
+fn test_legacy_queue_is_readable() {
+    let accounts = accounts();
+    let mut contract = InkClient::new(accounts.alice);
+
+    legacy_push_message(&mut contract, &123u128.encode());
+    legacy_push_message(&mut contract, &456u128.encode());
+
+    assert_eq!(0, contract.get_queue_head().unwrap());
+    assert_eq!(2, contract.get_queue_tail().unwrap());
+    assert_eq!(Some(456u128), contract.get_message(1).unwrap());
+
+    // the new messages follow the old ones
+    assert_eq!(Ok(2), contract.push_message(&789u128));
+    assert_eq!(Some(123u128), contract.get_message(0).unwrap());
+    assert_eq!(Some(789u128), contract.get_message(2).unwrap());
+
+    // the off-chain rollup reads the queue with the same keys
+    assert_eq!(
+        Some(3u32.encode()),
+        contract.inner_get_rollup_value(&b"q/_tail".to_vec())
+    );
+    assert_eq!(
+        Some(0u32.encode()),
+        contract.inner_get_rollup_value(&b"q/_head".to_vec())
+    );
+    let key = [b"q/".as_slice(), &0u32.encode()].concat();
+    assert_eq!(
+        Some(123u128.encode()),
+        contract.inner_get_rollup_value(&key)
+    );
+    let key = [b"q/".as_slice(), &2u32.encode()].concat();
+    assert_eq!(
+        Some(789u128.encode()),
+        contract.inner_get_rollup_value(&key)
+    );
+}
+
+#[ink::test]
+fn test_migrate_message_queue() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+    let mut contract = InkClient::new(accounts.alice);
+
+    for i in 0..5u128 {
+        legacy_push_message(&mut contract, &i.encode());
+    }
+    legacy_pop_message(&mut contract);
+
+    // only the admin can migrate the queue
+    change_caller(accounts.bob);
+    assert_eq!(
+        Err(RollupClientError::AccessControlError(
+            AccessControlError::MissingRole
+        )),
+        contract.migrate_message_queue(DEFAULT_CHANNEL, 2)
+    );
+
+    change_caller(accounts.alice);
+    assert_eq!(Ok(2), contract.migrate_message_queue(DEFAULT_CHANNEL, 2));
+
+    // a message is processed during the migration
+    assert_eq!(Ok(()), contract.pop_to(2));
+
+    assert_eq!(Ok(0), contract.migrate_message_queue(DEFAULT_CHANNEL, 10));
+    assert_eq!(Ok(0), contract.migrate_message_queue(DEFAULT_CHANNEL, 10));
+
+    // nothing is left in the key value store
+    for key in [b"q/_head".to_vec(), b"q/_tail".to_vec()] {
+        assert_eq!(
+            None,
+            contract.inner_get_channel_value(DEFAULT_CHANNEL, &key)
+        );
+    }
+    for id in 0..5u32 {
+        let key = [b"q/".as_slice(), &id.encode()].concat();
+        assert_eq!(
+            None,
+            contract.inner_get_channel_value(DEFAULT_CHANNEL, &key)
+        );
+    }
+
+    assert_eq!(2, contract.get_queue_head().unwrap());
+    assert_eq!(5, contract.get_queue_tail().unwrap());
+    assert_eq!(None, contract.get_message::<u128>(1).unwrap());
+    assert_eq!(Some(2u128), contract.get_message(2).unwrap());
+    assert_eq!(Some(4u128), contract.get_message(4).unwrap());
+    // no envelope for the migrated messages
+    assert_eq!(Ok(None), contract.get_message_envelope(4));
+}
+
+/// Only the number of storage accesses is compared here, the weights are measured by the e2e test `bench_push_and_pop`
+#[ink::test]
+fn test_storage_accesses_of_push_and_pop() {
+    let accounts = accounts();
+    let mut contract = InkClient::new(accounts.alice);
+    let message = 123456u128;
+
+    // the first message initializes the queue
+    contract.push_message(&message).unwrap();
+    legacy_push_message(&mut contract, &message.encode());
+
+    let push = storage_rw(|| {
+        contract.push_message(&message).unwrap();
+    });
+    let legacy_push = storage_rw(|| legacy_push_message(&mut contract, &message.encode()));
+    let pop = storage_rw(|| contract.pop_to(1).unwrap());
+    let legacy_pop = storage_rw(|| legacy_pop_message(&mut contract));
+
+    // the dedicated storage reads and writes less than the key value store
+    assert!(push.0 < legacy_push.0 && push.1 < legacy_push.1);
+    assert!(pop.0 < legacy_pop.0 && pop.1 < legacy_pop.1);
+}
+
+#[ink::test]
+fn test_reset_queue_indexes() {
+    let accounts = accounts();
+    let mut contract = InkClient::new(accounts.alice);
+
+    // the queue is close to the max index
+    let last_index = QueueIndex::MAX - 1;
+    let bounds = QueueBounds {
+        head: last_index,
+        tail: last_index,
+        pruned_to: last_index,
+    };
+    let first_queue = contract.queue_id();
+    contract.set_queue_bounds_of(first_queue, &bounds);
+    MessageQueueStorage::get_mut_storage(&mut contract)
+        .status
+        .insert((first_queue, 0), &MessageStatus::Cancelled);
+
+    assert_eq!(Ok(last_index), contract.push_message(&1u8));
+
+    // the indexes can't be reset while a message is not processed
+    assert_eq!(
+        Err(RollupClientError::QueueIndexOverflow),
+        contract.push_message(&2u8)
+    );
+
+    // the queue is empty, the indexes restart from 0
+    assert_eq!(Ok(()), contract.pop_to(QueueIndex::MAX));
+    assert_eq!(Ok(0), contract.push_message(&2u8));
+    assert_eq!(Ok(1), contract.push_message(&3u8));
+    assert_eq!(
+        Ok(QueueBounds {
+            head: 0,
+            tail: 2,
+            pruned_to: 0
+        }),
+        contract.get_queue_bounds_of(contract.queue_id())
+    );
+    assert_eq!(first_queue.epoch + 1, contract.queue_id().epoch);
+    assert_eq!(Ok(Some(2u8)), contract.get_message(0));
+    assert_eq!(Ok(true), contract.has_channel_message(DEFAULT_CHANNEL));
+    assert_eq!(Ok(2), contract.get_channel_queue_tail(DEFAULT_CHANNEL));
+
+    // the status of the old message is not applied to the new one
+    assert_eq!(None, contract.get_message_status(0));
+
+    // the off-chain rollup reads the new bounds
+    let head_key = [b"q/".as_slice(), b"_head"].concat();
+    let tail_key = [b"q/".as_slice(), b"_tail"].concat();
+    assert_eq!(
+        Some(0u32.encode()),
+        contract.inner_get_rollup_value(&head_key)
+    );
+    assert_eq!(
+        Some(2u32.encode()),
+        contract.inner_get_rollup_value(&tail_key)
+    );
+}
@@ -0,0 +1,109 @@
+mod contract;
+mod test_utils;
+
+use contract::test_contract::InkClient;
+use ink::scale::Encode;
+use inkv6_client_lib::traits::kv_store::KvStore;
+use inkv6_client_lib::traits::message_router::{MessageRouter, TypedEnvelope, TypedMessage};
+use inkv6_client_lib::traits::RollupClientError;
+use test_utils::accounts;
+
+#[derive(TypedMessage)]
+#[ink::scale_derive(Encode, Decode)]
+struct PriceResponseV1 {
+    price: u64,
+}
+
+#[derive(TypedMessage)]
+#[typed_message(name = "PriceResponseV1", version = 2)]
+#[ink::scale_derive(Encode, Decode)]
+struct PriceResponse {
+    price: u128,
+    decimals: u8,
+}
+
+#[derive(TypedMessage)]
+#[typed_message(id = 42)]
+#[ink::scale_derive(Encode, Decode)]
+struct ErrorResponse;
+
+fn on_price_v1(
+    contract: &mut InkClient,
+    message: PriceResponseV1,
+) -> Result<(), RollupClientError> {
+    contract.inner_set_value(&b"price".to_vec(), Some(&(message.price as u128).encode()))
+}
+
+fn on_price(contract: &mut InkClient, message: PriceResponse) -> Result<(), RollupClientError> {
+    contract.inner_set_value(&b"price".to_vec(), Some(&message.price.encode()))?;
+    contract.inner_set_value(&b"decimals".to_vec(), Some(&message.decimals.encode()))
+}
+
+fn route(contract: &mut InkClient, data: &[u8]) -> Result<(), RollupClientError> {
+    MessageRouter::new(contract, data)?
+        .route(on_price_v1)
+        .route(on_price)
+        .dispatch()
+}
+
+#[ink::test]
+fn test_type_ids() {
+    assert_eq!(
+        ink::selector_id!("PriceResponseV1"),
+        PriceResponseV1::TYPE_ID
+    );
+    assert_eq!(1, PriceResponseV1::VERSION);
+    assert_eq!(PriceResponseV1::TYPE_ID, PriceResponse::TYPE_ID);
+    assert_eq!(2, PriceResponse::VERSION);
+    assert_eq!(42, ErrorResponse::TYPE_ID);
+}
+
+#[ink::test]
+fn test_route_messages() {
+    let accounts = accounts();
+    let mut contract = InkClient::new(accounts.alice);
+
+    let message = TypedEnvelope::new(&PriceResponseV1 { price: 100 });
+    assert_eq!(Ok(()), route(&mut contract, &message.encode()));
+    assert_eq!(
+        Some(100u128.encode()),
+        contract.inner_get_value(&b"price".to_vec())
+    );
+
+    // the new version of the message is handled by another handler
+    let message = TypedEnvelope::new(&PriceResponse {
+        price: 200,
+        decimals: 8,
+    });
+    assert_eq!(Ok(()), route(&mut contract, &message.encode()));
+    assert_eq!(
+        Some(200u128.encode()),
+        contract.inner_get_value(&b"price".to_vec())
+    );
+    assert_eq!(
+        Some(8u8.encode()),
+        contract.inner_get_value(&b"decimals".to_vec())
+    );
+
+    // no handler for this type
+    let message = TypedEnvelope::new(&ErrorResponse);
+    assert_eq!(
+        Err(RollupClientError::UnsupportedAction),
+        route(&mut contract, &message.encode())
+    );
+
+    // the payload doesn't match the schema
+    let message = TypedEnvelope {
+        type_id: PriceResponse::TYPE_ID,
+        version: PriceResponse::VERSION,
+        payload: vec![1, 2],
+    };
+    assert_eq!(
+        Err(RollupClientError::FailedToDecode),
+        route(&mut contract, &message.encode())
+    );
+    assert_eq!(
+        Err(RollupClientError::FailedToDecode),
+        route(&mut contract, &[1, 2])
+    );
+}
@@ -10,6 +10,7 @@ use inkv6_client_lib::traits::rollup_client::*;
 
 use contract::test_contract::InkClient;
 use inkv6_client_lib::traits::access_control::{AccessControl, AccessControlError};
+use inkv6_client_lib::traits::channel::MultiChannelRollupClient;
 use inkv6_client_lib::traits::RollupClientError;
 use test_utils::accounts;
 
@@ -280,3 +281,79 @@ fn test_meta_tx_rollup_cond_eq_missing_role() {
         contract.meta_tx_rollup_cond_eq(request.clone(), signature)
     );
 }
+
+#[ink::test]
+fn test_meta_tx_channel_rollup_cond_eq() {
+    let contract_address = Address::from([0xFFu8; 20]);
+    set_callee(contract_address);
+
+    let accounts = accounts();
+    let mut contract = InkClient::new(accounts.alice);
+
+    // ecdsa public key d'Alice
+    let keypair = subxt_signer::ecdsa::dev::alice();
+    let from = AccountIdMapper::to_address(&keypair.public_key().to_account_id().0);
+
+    // the signer is attestor in the channel 1 only
+    set_caller(accounts.alice);
+    contract
+        .grant_channel_role(1, ATTESTOR_ROLE, from)
+        .expect("Error when grant the role Attestor");
+
+    set_caller(accounts.bob);
+
+    // missing role in the channel 2
+    let data = ChannelRollupCondEqMethodParams::encode(&(
+        2,
+        vec![],
+        vec![(b"k".to_vec(), Some(b"v".to_vec()))],
+        vec![],
+    ));
+    let (request, _hash) = contract
+        .prepare(from, data)
+        .expect("Error when preparing meta tx");
+    let signature = keypair.sign(&ink::scale::Encode::encode(&request)).0;
+    assert_eq!(
+        Err(RollupClientError::AccessControlError(
+            AccessControlError::MissingRole
+        )),
+        contract.meta_tx_channel_rollup_cond_eq(request, signature)
+    );
+
+    // the value is saved in the channel 1
+    let data = ChannelRollupCondEqMethodParams::encode(&(
+        1,
+        vec![],
+        vec![(b"k".to_vec(), Some(b"v".to_vec()))],
+        vec![],
+    ));
+    let (request, _hash) = contract
+        .prepare(from, data)
+        .expect("Error when preparing meta tx");
+    let signature = keypair.sign(&ink::scale::Encode::encode(&request)).0;
+    assert_eq!(
+        Ok(()),
+        contract.meta_tx_channel_rollup_cond_eq(request.clone(), signature)
+    );
+    assert_eq!(
+        Some(b"v".to_vec()),
+        contract.get_channel_value(1, b"k".to_vec())
+    );
+    assert_eq!(None, contract.get_value(b"k".to_vec()));
+
+    // do it again => it must fail
+    assert_eq!(
+        Err(RollupClientError::NonceTooLow),
+        contract.meta_tx_channel_rollup_cond_eq(request, signature)
+    );
+
+    // invalid data
+    let (request, _hash) = contract
+        .prepare(from, vec![])
+        .expect("Error when preparing meta tx");
+    let signature = keypair.sign(&ink::scale::Encode::encode(&request)).0;
+    assert_eq!(
+        Err(RollupClientError::FailedToDecode),
+        contract.meta_tx_channel_rollup_cond_eq(request, signature)
+    );
+}
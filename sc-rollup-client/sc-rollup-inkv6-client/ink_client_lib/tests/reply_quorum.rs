@@ -0,0 +1,245 @@
+mod contract;
+mod test_utils;
+
+use contract::test_contract::InkClient;
+use ink::env::DefaultEnvironment;
+use ink::scale::Encode;
+use ink::Address;
+use inkv6_client_lib::traits::access_control::{AccessControl, AccessControlError};
+use inkv6_client_lib::traits::kv_store::KvStore;
+use inkv6_client_lib::traits::message_queue::{MessageQueue, MessageStatus};
+use inkv6_client_lib::traits::reply_quorum::{QuorumConfig, ReplyQuorum};
+use inkv6_client_lib::traits::rollup_client::*;
+use inkv6_client_lib::traits::RollupClientError;
+use test_utils::{accounts, change_caller};
+
+fn reply_to(
+    contract: &mut InkClient,
+    attestor: Address,
+    request_id: u32,
+    payload: u8,
+) -> Result<(), RollupClientError> {
+    change_caller(attestor);
+    let actions = vec![HandleActionInput::ReplyTo {
+        request_id,
+        payload: payload.encode(),
+    }];
+    contract.rollup_cond_eq(vec![], vec![], actions)
+}
+
+fn last_reply(contract: &InkClient) -> Option<Vec<u8>> {
+    contract.inner_get_value(&b"app/reply".to_vec())
+}
+
+#[ink::test]
+fn test_reply_quorum() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    for attestor in [
+        accounts.alice,
+        accounts.bob,
+        accounts.charlie,
+        accounts.django,
+    ] {
+        assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, attestor));
+    }
+    let config = QuorumConfig {
+        quorum: 3,
+        timeout: 10,
+    };
+
+    // only the admin can set the quorum
+    change_caller(accounts.bob);
+    assert_eq!(
+        Err(RollupClientError::AccessControlError(
+            AccessControlError::MissingRole
+        )),
+        contract.set_quorum_config(config.clone())
+    );
+    change_caller(accounts.alice);
+    assert_eq!(Ok(()), contract.set_quorum_config(config.clone()));
+    assert_eq!(config, contract.get_quorum_config());
+
+    contract.push_message(&10u8).unwrap();
+
+    // the replies are collected until the quorum is reached
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.alice, 0, 1));
+    assert_eq!(
+        Err(RollupClientError::DuplicateReply),
+        reply_to(&mut contract, accounts.alice, 0, 1)
+    );
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.bob, 0, 2));
+    assert_eq!(2, contract.get_pending_replies(0).unwrap().replies.len());
+    assert_eq!(None, last_reply(&contract));
+
+    // the reply sent by more than half of the quorum is handled
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.charlie, 0, 1));
+    assert_eq!(None, contract.get_pending_replies(0));
+    assert_eq!(
+        Some((10u8.encode(), 1u8.encode()).encode()),
+        last_reply(&contract)
+    );
+    assert_eq!(
+        Err(RollupClientError::MessageAlreadyAnswered),
+        reply_to(&mut contract, accounts.django, 0, 1)
+    );
+}
+
+#[ink::test]
+fn test_reply_quorum_timeout() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    for attestor in [accounts.alice, accounts.bob, accounts.charlie] {
+        assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, attestor));
+    }
+    let config = QuorumConfig {
+        quorum: 4,
+        timeout: 10,
+    };
+    assert_eq!(Ok(()), contract.set_quorum_config(config));
+
+    contract.push_message(&10u8).unwrap();
+    contract.push_message(&11u8).unwrap();
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.alice, 0, 5));
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.bob, 0, 5));
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.charlie, 0, 5));
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.alice, 1, 6));
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.bob, 1, 6));
+
+    // the quorum is not reached and the timeout is not passed
+    let actions = vec![HandleActionInput::FinalizeReplies(0)];
+    assert_eq!(
+        Err(RollupClientError::QuorumNotReached),
+        contract.rollup_cond_eq(vec![], vec![], actions.clone())
+    );
+
+    for _ in 0..10 {
+        ink::env::test::advance_block::<DefaultEnvironment>();
+    }
+
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(
+        Some((10u8.encode(), 5u8.encode()).encode()),
+        last_reply(&contract)
+    );
+
+    // after the timeout, the reply must still be sent by more than half of the quorum,
+    // otherwise the request is closed without reply
+    let finalize = vec![HandleActionInput::FinalizeReplies(1)];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], finalize));
+    assert_eq!(
+        Some((10u8.encode(), 5u8.encode()).encode()),
+        last_reply(&contract)
+    );
+    assert_eq!(Some(MessageStatus::Failed), contract.get_message_status(1));
+    assert_eq!(
+        Some(1u32.encode()),
+        contract.get_value(b"app/failed".to_vec())
+    );
+}
+
+#[ink::test]
+fn test_divergent_replies() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    for attestor in [
+        accounts.alice,
+        accounts.bob,
+        accounts.charlie,
+        accounts.django,
+    ] {
+        assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, attestor));
+    }
+    let config = QuorumConfig {
+        quorum: 3,
+        timeout: 10,
+    };
+    assert_eq!(Ok(()), contract.set_quorum_config(config));
+
+    contract.push_message(&10u8).unwrap();
+    contract.push_message(&11u8).unwrap();
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.alice, 1, 1));
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.bob, 1, 2));
+
+    // the attestor completing the quorum is not blocked by the replies which don't agree
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.charlie, 1, 3));
+    assert_eq!(None, last_reply(&contract));
+    assert_eq!(None, contract.get_pending_replies(1));
+    assert_eq!(Some(MessageStatus::Failed), contract.get_message_status(1));
+    assert_eq!(
+        Some(1u32.encode()),
+        contract.get_value(b"app/failed".to_vec())
+    );
+
+    // the request is closed
+    assert_eq!(Ok(None), RollupClient::get_message(&contract, 1));
+    assert_eq!(
+        Err(RollupClientError::MessageNotPending),
+        reply_to(&mut contract, accounts.django, 1, 1)
+    );
+
+    // and skipped by the head
+    let actions = vec![HandleActionInput::Ack(vec![0])];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(Ok(false), MessageQueue::has_message(&contract));
+}
+
+#[ink::test]
+fn test_reply_quorum_without_timeout() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+    let config = QuorumConfig {
+        quorum: 2,
+        timeout: 0,
+    };
+    assert_eq!(Ok(()), contract.set_quorum_config(config));
+
+    contract.push_message(&10u8).unwrap();
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.alice, 0, 5));
+
+    // a single attestor can't finalize its own reply
+    let actions = vec![HandleActionInput::FinalizeReplies(0)];
+    assert_eq!(
+        Err(RollupClientError::QuorumNotReached),
+        contract.rollup_cond_eq(vec![], vec![], actions)
+    );
+    assert!(contract.get_pending_replies(0).is_some());
+    assert_eq!(None, last_reply(&contract));
+}
+
+#[ink::test]
+fn test_pending_replies_removed_when_the_request_is_closed() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+    let config = QuorumConfig {
+        quorum: 2,
+        timeout: 10,
+    };
+    assert_eq!(Ok(()), contract.set_quorum_config(config));
+
+    contract.push_message(&10u8).unwrap();
+    contract.push_message(&11u8).unwrap();
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.alice, 0, 5));
+    assert_eq!(Ok(()), reply_to(&mut contract, accounts.alice, 1, 6));
+
+    // the replies are removed when the request is cancelled
+    assert_eq!(Ok(()), contract.cancel(0));
+    assert_eq!(None, contract.get_pending_replies(0));
+
+    // or skipped by the action `SetQueueHead`
+    let actions = vec![HandleActionInput::SetQueueHead(2)];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(None, contract.get_pending_replies(1));
+}
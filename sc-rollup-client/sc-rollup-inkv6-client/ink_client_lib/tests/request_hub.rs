@@ -0,0 +1,107 @@
+mod contract;
+mod test_utils;
+
+use contract::test_contract::InkClient;
+use inkv6_client_lib::traits::access_control::AccessControlError;
+use inkv6_client_lib::traits::message_queue::MessageQueue;
+use inkv6_client_lib::traits::request_hub::{
+    CallbackLimits, HubMessage, HubRequest, RequestHub, HUB_REQUEST_TYPE,
+};
+use inkv6_client_lib::traits::rollup_client::*;
+use inkv6_client_lib::traits::RollupClientError;
+use test_utils::{accounts, change_caller};
+
+#[ink::test]
+fn test_request_from_another_contract() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+
+    // the consumer contracts send the requests
+    change_caller(accounts.bob);
+    assert_eq!(Ok(0), contract.request(b"BTC/USD".to_vec(), [1, 2, 3, 4]));
+    change_caller(accounts.charlie);
+    assert_eq!(Ok(1), contract.request(b"ETH/USD".to_vec(), [5, 6, 7, 8]));
+
+    // the worker knows which contract sends the request
+    assert_eq!(
+        Ok(Some(HubMessage {
+            requester: accounts.bob,
+            payload: b"BTC/USD".to_vec(),
+        })),
+        MessageQueue::get_message(&contract, 0)
+    );
+    assert_eq!(
+        Some(HubRequest {
+            requester: accounts.charlie,
+            callback_selector: [5, 6, 7, 8],
+        }),
+        contract.get_hub_request(1)
+    );
+
+    // the requester can cancel its request
+    assert_eq!(Ok(()), contract.cancel(1));
+    assert_eq!(None, contract.get_hub_request(1));
+    assert!(contract.get_hub_request(0).is_some());
+}
+
+#[ink::test]
+fn test_hub_request_released_when_the_message_is_deleted() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    change_caller(accounts.bob);
+    for _ in 0..3 {
+        contract.request(b"BTC/USD".to_vec(), [1, 2, 3, 4]).unwrap();
+    }
+
+    // the messages pushed by the hub are tagged
+    let envelope = MessageQueueReader::get_message_envelope(&contract, 0)
+        .unwrap()
+        .unwrap();
+    assert_eq!(Some(HUB_REQUEST_TYPE), envelope.type_tag);
+
+    // the request acknowledged without reply is released when the message is deleted
+    change_caller(accounts.alice);
+    let actions = vec![HandleActionInput::Ack(vec![1])];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert!(contract.get_hub_request(1).is_some());
+
+    // the requests skipped by the action `SetQueueHead` are released too
+    let actions = vec![HandleActionInput::SetQueueHead(2)];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(None, contract.get_hub_request(0));
+    assert_eq!(None, contract.get_hub_request(1));
+    assert!(contract.get_hub_request(2).is_some());
+}
+
+#[ink::test]
+fn test_callback_limits() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(CallbackLimits::default(), contract.get_callback_limits());
+
+    let limits = CallbackLimits {
+        ref_time: 1_000_000,
+        proof_size: 1_024,
+    };
+
+    // only the admin can set the limits
+    change_caller(accounts.bob);
+    assert_eq!(
+        Err(RollupClientError::AccessControlError(
+            AccessControlError::MissingRole
+        )),
+        contract.set_callback_limits(limits.clone())
+    );
+
+    change_caller(accounts.alice);
+    assert_eq!(Ok(()), contract.set_callback_limits(limits.clone()));
+    assert_eq!(limits, contract.get_callback_limits());
+}
@@ -1,10 +1,14 @@
 mod contract;
 mod test_utils;
 use contract::test_contract::InkClient;
+use ink::env::DefaultEnvironment;
 use ink::prelude::vec::Vec;
 use ink::scale::Encode;
 use inkv6_client_lib::traits::access_control::{AccessControl, AccessControlError};
-use inkv6_client_lib::traits::message_queue::MessageQueue;
+use inkv6_client_lib::traits::message_queue::{
+    MessageQueue, MessageQueueQuota, MessageStatus, QueueLimits, TopicQueue,
+    DEFAULT_MAX_LEASE_BLOCKS, MAX_ACTIVE_LEASES,
+};
 use inkv6_client_lib::traits::rollup_client::*;
 use inkv6_client_lib::traits::RollupClientError;
 use test_utils::{accounts, change_caller};
@@ -36,14 +40,14 @@ fn test_conditions() {
     let conditions = vec![(123u8.encode(), Some(789u128.encode()))];
     assert_eq!(
         contract.rollup_cond_eq(conditions, vec![], vec![]),
-        Err(RollupClientError::ConditionNotMet)
+        Err(RollupClientError::ConditionNotMet(0))
     );
 
     // test with incorrect condition (incorrect value)
     let conditions = vec![(123u8.encode(), None)];
     assert_eq!(
         contract.rollup_cond_eq(conditions, vec![], vec![]),
-        Err(RollupClientError::ConditionNotMet)
+        Err(RollupClientError::ConditionNotMet(0))
     );
 
     // test with incorrect condition (incorrect key)
@@ -53,7 +57,69 @@ fn test_conditions() {
     ];
     assert_eq!(
         contract.rollup_cond_eq(conditions, vec![], vec![]),
-        Err(RollupClientError::ConditionNotMet)
+        Err(RollupClientError::ConditionNotMet(1))
+    );
+}
+
+#[ink::test]
+fn test_check_conditions() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    let updates = vec![
+        (123u8.encode(), Some(456u128.encode())),
+        (124u8.encode(), Some(789u128.encode())),
+    ];
+    assert_eq!(contract.rollup_cond_eq(vec![], updates, vec![]), Ok(()));
+
+    let conditions = vec![
+        (123u8.encode(), Some(456u128.encode())),
+        (124u8.encode(), Some(456u128.encode())),
+        (125u8.encode(), None),
+        (125u8.encode(), Some(456u128.encode())),
+    ];
+    assert_eq!(
+        contract.check_conditions(conditions.clone()),
+        Ok(vec![
+            ConditionResult {
+                is_met: true,
+                current_value: Some(456u128.encode())
+            },
+            ConditionResult {
+                is_met: false,
+                current_value: Some(789u128.encode())
+            },
+            ConditionResult {
+                is_met: true,
+                current_value: None
+            },
+            ConditionResult {
+                is_met: false,
+                current_value: None
+            },
+        ])
+    );
+
+    // the error gives the index of the first condition not met
+    assert_eq!(
+        contract.rollup_cond_eq(conditions, vec![], vec![]),
+        Err(RollupClientError::ConditionNotMet(1))
+    );
+
+    // the reserved keys are rejected like in a rollup transaction
+    contract.push_message(&1u8).unwrap();
+    let head_key = [b"q/".as_slice(), b"_head"].concat();
+    let conditions = vec![(head_key, Some(0u32.encode()))];
+    assert_eq!(
+        contract.check_conditions(conditions.clone()),
+        Err(RollupClientError::ReservedKey)
+    );
+    assert_eq!(
+        contract.rollup_cond_eq(conditions, vec![], vec![]),
+        Err(RollupClientError::ReservedKey)
     );
 }
 
@@ -94,6 +160,64 @@ fn test_action_reply() {
     assert_eq!(contract.rollup_cond_eq(vec![], vec![], actions), Ok(()));
 }
 
+#[ink::test]
+fn test_action_reply_to() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    assert_eq!(Ok(0), contract.push_message(&1u8));
+    assert_eq!(Ok(1), contract.push_message(&2u8));
+
+    let actions = vec![HandleActionInput::ReplyTo {
+        request_id: 1,
+        payload: 20u8.encode(),
+    }];
+    assert_eq!(
+        Ok(()),
+        contract.rollup_cond_eq(vec![], vec![], actions.clone())
+    );
+    assert_eq!(
+        Some(MessageStatus::Answered),
+        contract.get_message_status(1)
+    );
+
+    // the hook receives the request and the response
+    assert_eq!(
+        Some((2u8.encode(), 20u8.encode()).encode()),
+        contract.get_value(b"app/reply".to_vec())
+    );
+
+    // only one response by request
+    assert_eq!(
+        Err(RollupClientError::MessageAlreadyAnswered),
+        contract.rollup_cond_eq(vec![], vec![], actions)
+    );
+
+    // the request must be pending
+    let actions = vec![
+        HandleActionInput::SetQueueHead(1),
+        HandleActionInput::ReplyTo {
+            request_id: 0,
+            payload: 10u8.encode(),
+        },
+    ];
+    assert_eq!(
+        Err(RollupClientError::MessageNotPending),
+        contract.rollup_cond_eq(vec![], vec![], actions)
+    );
+    let actions = vec![HandleActionInput::ReplyTo {
+        request_id: 2,
+        payload: 30u8.encode(),
+    }];
+    assert_eq!(
+        Err(RollupClientError::MessageNotPending),
+        contract.rollup_cond_eq(vec![], vec![], actions)
+    );
+}
+
 #[ink::test]
 fn test_grant_revoke_attestor() {
     let accounts = accounts();
@@ -135,3 +259,783 @@ fn test_rollup_cond_eq_role_attestor() {
     change_caller(accounts.bob);
     assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], vec![]));
 }
+
+#[ink::test]
+fn test_reserved_keys() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    let message = 4589u16;
+    contract.push_message(&message).unwrap();
+
+    // the keys used by the message queue can't be updated directly
+    let updates = vec![(b"q/_head".to_vec(), Some(1u32.encode()))];
+    assert_eq!(
+        contract.rollup_cond_eq(vec![], updates, vec![]),
+        Err(RollupClientError::ReservedKey)
+    );
+    let updates = vec![(b"q/_tail".to_vec(), None)];
+    assert_eq!(
+        contract.rollup_cond_eq(vec![], updates, vec![]),
+        Err(RollupClientError::ReservedKey)
+    );
+    let conditions = vec![([b"q/".as_slice(), &0u32.encode()].concat(), None)];
+    assert_eq!(
+        contract.rollup_cond_eq(conditions, vec![], vec![]),
+        Err(RollupClientError::ReservedKey)
+    );
+
+    // the keys reserved by the contract can't be used either
+    let updates = vec![(b"app/config".to_vec(), Some(1u32.encode()))];
+    assert_eq!(
+        contract.rollup_cond_eq(vec![], updates, vec![]),
+        Err(RollupClientError::ReservedKey)
+    );
+
+    // the queue is not corrupted
+    assert_eq!(0, contract.get_queue_head().unwrap());
+    assert_eq!(1, contract.get_queue_tail().unwrap());
+
+    // but the action is still allowed
+    let actions = vec![HandleActionInput::SetQueueHead(1)];
+    assert_eq!(contract.rollup_cond_eq(vec![], vec![], actions), Ok(()));
+    assert_eq!(1, contract.get_queue_head().unwrap());
+
+    // other keys are allowed
+    let updates = vec![(b"apps".to_vec(), Some(1u32.encode()))];
+    assert_eq!(contract.rollup_cond_eq(vec![], updates, vec![]), Ok(()));
+}
+
+#[ink::test]
+fn test_key_metadata() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.bob));
+
+    let key = 123u8.encode();
+    assert_eq!((None, None), contract.get_value_with_meta(key.clone()));
+
+    // alice updates the value
+    ink::env::test::set_block_timestamp::<DefaultEnvironment>(1_000);
+    let updates = vec![(key.clone(), Some(456u128.encode()))];
+    assert_eq!(contract.rollup_cond_eq(vec![], updates, vec![]), Ok(()));
+
+    let (value, metadata) = contract.get_value_with_meta(key.clone());
+    assert_eq!(Some(456u128.encode()), value);
+    let metadata = metadata.unwrap();
+    assert_eq!(1_000, metadata.timestamp);
+    assert_eq!(accounts.alice, metadata.attestor);
+    assert_eq!(1, metadata.batch_sequence);
+
+    // a transaction without update doesn't change the sequence
+    assert_eq!(contract.rollup_cond_eq(vec![], vec![], vec![]), Ok(()));
+
+    // bob removes the value in another block
+    ink::env::test::advance_block::<DefaultEnvironment>();
+    ink::env::test::set_block_timestamp::<DefaultEnvironment>(2_000);
+    change_caller(accounts.bob);
+    let updates = vec![(key.clone(), None)];
+    assert_eq!(contract.rollup_cond_eq(vec![], updates, vec![]), Ok(()));
+
+    let (value, new_metadata) = contract.get_value_with_meta(key);
+    assert_eq!(None, value);
+    let new_metadata = new_metadata.unwrap();
+    assert_eq!(metadata.block_number + 1, new_metadata.block_number);
+    assert_eq!(2_000, new_metadata.timestamp);
+    assert_eq!(accounts.bob, new_metadata.attestor);
+    assert_eq!(2, new_metadata.batch_sequence);
+}
+
+#[ink::test]
+fn test_message_deadline() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    let deadline = ink::env::block_number::<DefaultEnvironment>() + 1;
+    assert_eq!(Ok(0), contract.push_message_with_deadline(&1u8, deadline));
+    assert_eq!(Ok(1), contract.push_message(&2u8));
+    assert_eq!(Ok(2), contract.push_message_with_deadline(&3u8, deadline));
+
+    // the deadline is not passed
+    assert_eq!(
+        Err(RollupClientError::DeadlineNotReached),
+        contract.expire(0)
+    );
+    let actions = vec![HandleActionInput::ReplyTo {
+        request_id: 2,
+        payload: 12u8.encode(),
+    }];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+
+    ink::env::test::advance_block::<DefaultEnvironment>();
+    ink::env::test::advance_block::<DefaultEnvironment>();
+
+    // the reply is rejected once the deadline is passed
+    let actions = vec![HandleActionInput::ReplyTo {
+        request_id: 0,
+        payload: 12u8.encode(),
+    }];
+    assert_eq!(
+        Err(RollupClientError::MessageExpired),
+        contract.rollup_cond_eq(vec![], vec![], actions)
+    );
+
+    // an answered request doesn't expire
+    assert_eq!(
+        Err(RollupClientError::MessageNotPending),
+        contract.expire(2)
+    );
+
+    // a message without deadline doesn't expire
+    assert_eq!(
+        Err(RollupClientError::DeadlineNotReached),
+        contract.expire(1)
+    );
+
+    // anyone can mark the message as expired
+    change_caller(accounts.bob);
+    assert_eq!(Ok(()), contract.expire(0));
+    assert_eq!(
+        Some(0u32.encode()),
+        contract.get_value(b"app/expired".to_vec())
+    );
+    let key = [b"q/".as_slice(), &0u32.encode()].concat();
+    assert_eq!(None, contract.get_value(key));
+    assert_eq!(
+        Err(RollupClientError::MessageNotPending),
+        contract.expire(0)
+    );
+
+    // same with the action
+    change_caller(accounts.alice);
+    let actions = vec![HandleActionInput::ExpireMessages(vec![0])];
+    assert_eq!(
+        Err(RollupClientError::MessageNotPending),
+        contract.rollup_cond_eq(vec![], vec![], actions)
+    );
+
+    // the head is moved after the expired message
+    assert_eq!(Ok(1), MessageQueue::get_queue_head(&contract));
+    assert_eq!(Ok(true), MessageQueue::has_message(&contract));
+    assert_eq!(None, MessageQueue::get_message::<u8>(&contract, 0).unwrap());
+}
+
+#[ink::test]
+fn test_reply_when_next_message_is_expired() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    let deadline = ink::env::block_number::<DefaultEnvironment>() + 1;
+    assert_eq!(Ok(0), contract.push_message(&1u8));
+    assert_eq!(Ok(1), contract.push_message_with_deadline(&2u8, deadline));
+    assert_eq!(Ok(2), contract.push_message(&3u8));
+
+    ink::env::test::advance_block::<DefaultEnvironment>();
+    ink::env::test::advance_block::<DefaultEnvironment>();
+
+    // the worker moves the head before sending the reply of the message it has consumed:
+    // the message now at the head is expired but the reply is sent for the previous one
+    let actions = vec![
+        HandleActionInput::SetQueueHead(1),
+        HandleActionInput::Reply(12u8.encode()),
+    ];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(Ok(1), MessageQueue::get_queue_head(&contract));
+
+    // the reply to the expired message is rejected
+    let actions = vec![
+        HandleActionInput::SetQueueHead(2),
+        HandleActionInput::ReplyTo {
+            request_id: 1,
+            payload: 22u8.encode(),
+        },
+    ];
+    assert_eq!(
+        Err(RollupClientError::MessageNotPending),
+        contract.rollup_cond_eq(vec![], vec![], actions)
+    );
+    let actions = vec![HandleActionInput::ReplyTo {
+        request_id: 1,
+        payload: 22u8.encode(),
+    }];
+    assert_eq!(
+        Err(RollupClientError::MessageExpired),
+        contract.rollup_cond_eq(vec![], vec![], actions)
+    );
+
+    // the worker can still consume the expired message and the next one
+    let actions = vec![
+        HandleActionInput::ExpireMessages(vec![1]),
+        HandleActionInput::SetQueueHead(3),
+        HandleActionInput::Reply(32u8.encode()),
+    ];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(Ok(false), MessageQueue::has_message(&contract));
+}
+
+#[ink::test]
+fn test_action_ack() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    for i in 0..4u8 {
+        contract.push_message(&i).unwrap();
+    }
+
+    // the workers reply to the messages in parallel
+    let actions = vec![HandleActionInput::Ack(vec![1, 3])];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(0, contract.get_queue_bounds().unwrap().head);
+    assert_eq!(
+        Ok(vec![(0, 0u8.encode()), (2, 2u8.encode())]),
+        contract.get_messages(0, 10)
+    );
+
+    // a message can't be acknowledged twice
+    let actions = vec![HandleActionInput::Ack(vec![1])];
+    assert_eq!(
+        Err(RollupClientError::MessageNotPending),
+        contract.rollup_cond_eq(vec![], vec![], actions)
+    );
+
+    let actions = vec![HandleActionInput::Ack(vec![0])];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(2, contract.get_queue_bounds().unwrap().head);
+
+    let actions = vec![HandleActionInput::Ack(vec![2])];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(4, contract.get_queue_bounds().unwrap().head);
+    assert_eq!(Ok(false), MessageQueue::has_message(&contract));
+}
+
+#[ink::test]
+fn test_action_set_topic_queue_head() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    const URGENT: u32 = ink::selector_id!("urgent");
+
+    contract.push_message(&1u8).unwrap();
+    for i in 0..3u8 {
+        contract.push_message_to(URGENT, &i).unwrap();
+    }
+
+    assert_eq!(
+        Ok(vec![
+            (0, 0u8.encode()),
+            (1, 1u8.encode()),
+            (2, 2u8.encode())
+        ]),
+        contract.get_topic_messages(URGENT, 0, 10)
+    );
+
+    let actions = vec![HandleActionInput::SetTopicQueueHead {
+        topic: URGENT,
+        id: 2,
+    }];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+
+    let bounds = contract.get_topic_queue_bounds(URGENT).unwrap();
+    assert_eq!(2, bounds.head);
+    assert_eq!(3, bounds.tail);
+    assert_eq!(
+        Ok(vec![(2, 2u8.encode())]),
+        contract.get_topic_messages(URGENT, 0, 10)
+    );
+
+    // the default queue is not impacted
+    let bounds = contract.get_queue_bounds().unwrap();
+    assert_eq!(0, bounds.head);
+    assert_eq!(1, bounds.tail);
+
+    // the head can't be moved after the tail of the topic
+    let actions = vec![HandleActionInput::SetTopicQueueHead {
+        topic: URGENT,
+        id: 4,
+    }];
+    assert_eq!(
+        Err(RollupClientError::InvalidPopTarget),
+        contract.rollup_cond_eq(vec![], vec![], actions)
+    );
+}
+
+#[ink::test]
+fn test_action_lease() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.bob));
+
+    for i in 0..4u8 {
+        contract.push_message(&i).unwrap();
+    }
+
+    // alice claims the first messages
+    let until_block = ink::env::block_number::<DefaultEnvironment>() + 1;
+    let actions = vec![HandleActionInput::Lease {
+        from: 0,
+        to: 2,
+        until_block,
+    }];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    let lease = MessageQueueReader::get_message_lease(&contract, 1).unwrap();
+    assert_eq!(accounts.alice, lease.attestor);
+    assert_eq!(until_block, lease.until_block);
+    assert_eq!(None, MessageQueueReader::get_message_lease(&contract, 2));
+
+    // bob can't reply to the leased messages or claim them
+    change_caller(accounts.bob);
+    let reply = vec![HandleActionInput::ReplyTo {
+        request_id: 0,
+        payload: 1u8.encode(),
+    }];
+    assert_eq!(
+        Err(RollupClientError::MessageLeased),
+        contract.rollup_cond_eq(vec![], vec![], reply.clone())
+    );
+    let actions = vec![HandleActionInput::Ack(vec![1])];
+    assert_eq!(
+        Err(RollupClientError::MessageLeased),
+        contract.rollup_cond_eq(vec![], vec![], actions)
+    );
+
+    // bob can't consume the leased messages with the other actions
+    for action in [
+        HandleActionInput::SetQueueHead(2),
+        HandleActionInput::FinalizeReplies(0),
+    ] {
+        assert_eq!(
+            Err(RollupClientError::MessageLeased),
+            contract.rollup_cond_eq(vec![], vec![], vec![action])
+        );
+    }
+    let lease_bob = vec![HandleActionInput::Lease {
+        from: 1,
+        to: 3,
+        until_block: until_block + 5,
+    }];
+    assert_eq!(
+        Err(RollupClientError::MessageLeased),
+        contract.rollup_cond_eq(vec![], vec![], lease_bob.clone())
+    );
+
+    // alice replies to her messages
+    change_caller(accounts.alice);
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], reply));
+
+    // the expired leases can be claimed again
+    ink::env::test::advance_block::<DefaultEnvironment>();
+    ink::env::test::advance_block::<DefaultEnvironment>();
+    change_caller(accounts.bob);
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], lease_bob));
+    assert_eq!(
+        accounts.bob,
+        MessageQueueReader::get_message_lease(&contract, 1)
+            .unwrap()
+            .attestor
+    );
+
+    // invalid ranges
+    let current_block = ink::env::block_number::<DefaultEnvironment>();
+    for (from, to, until_block) in [
+        (3, 3, current_block + 1),
+        (3, 200, current_block + 1),
+        (3, 4, current_block - 1),
+        (3, 4, current_block + DEFAULT_MAX_LEASE_BLOCKS + 1),
+    ] {
+        let actions = vec![HandleActionInput::Lease {
+            from,
+            to,
+            until_block,
+        }];
+        assert_eq!(
+            Err(RollupClientError::InvalidLeaseRange),
+            contract.rollup_cond_eq(vec![], vec![], actions)
+        );
+    }
+    let actions = vec![HandleActionInput::Lease {
+        from: 3,
+        to: 5,
+        until_block: current_block + 1,
+    }];
+    assert_eq!(
+        Err(RollupClientError::MessageNotPending),
+        contract.rollup_cond_eq(vec![], vec![], actions)
+    );
+}
+
+#[ink::test]
+fn test_reply_when_next_messages_are_leased() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.bob));
+
+    for i in 0..4u8 {
+        contract.push_message(&i).unwrap();
+    }
+
+    // alice claims the messages following the head
+    let until_block = ink::env::block_number::<DefaultEnvironment>() + 10;
+    let actions = vec![HandleActionInput::Lease {
+        from: 1,
+        to: 3,
+        until_block,
+    }];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+
+    // bob moves the head before sending the reply of the message he has consumed (not leased)
+    change_caller(accounts.bob);
+    let actions = vec![
+        HandleActionInput::SetQueueHead(1),
+        HandleActionInput::Reply(10u8.encode()),
+    ];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(Ok(1), MessageQueue::get_queue_head(&contract));
+
+    // but he can't consume the messages leased by alice
+    let actions = vec![
+        HandleActionInput::SetQueueHead(2),
+        HandleActionInput::Reply(11u8.encode()),
+    ];
+    assert_eq!(
+        Err(RollupClientError::MessageLeased),
+        contract.rollup_cond_eq(vec![], vec![], actions)
+    );
+
+    // alice consumes them
+    change_caller(accounts.alice);
+    let actions = vec![
+        HandleActionInput::SetQueueHead(3),
+        HandleActionInput::Reply(11u8.encode()),
+        HandleActionInput::Reply(12u8.encode()),
+    ];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(Ok(3), MessageQueue::get_queue_head(&contract));
+}
+
+#[ink::test]
+fn test_cancel_message() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    // the messages are pushed by bob
+    change_caller(accounts.bob);
+    for i in 0..4u8 {
+        contract.push_message(&i).unwrap();
+    }
+
+    // only the sender or an admin can cancel the message
+    change_caller(accounts.charlie);
+    assert_eq!(
+        Err(RollupClientError::AccessControlError(
+            AccessControlError::MissingRole
+        )),
+        contract.cancel(1)
+    );
+
+    change_caller(accounts.bob);
+    assert_eq!(Ok(()), contract.cancel(1));
+    assert_eq!(
+        Some(MessageStatus::Cancelled),
+        contract.get_message_status(1)
+    );
+    assert_eq!(
+        Some(1u32.encode()),
+        contract.get_value(b"app/cancelled".to_vec())
+    );
+    assert_eq!(
+        Err(RollupClientError::MessageNotPending),
+        contract.cancel(1)
+    );
+
+    change_caller(accounts.alice);
+    assert_eq!(Ok(()), contract.cancel(2));
+
+    // the workers skip the cancelled messages and their replies are rejected
+    assert_eq!(Ok(None), RollupClient::get_message(&contract, 1));
+    let key = [b"q/".as_slice(), &1u32.encode()].concat();
+    assert_eq!(None, contract.get_value(key));
+    assert_eq!(
+        Ok(vec![(0, 0u8.encode()), (3, 3u8.encode())]),
+        contract.get_messages(0, 10)
+    );
+    let actions = vec![HandleActionInput::ReplyTo {
+        request_id: 1,
+        payload: 10u8.encode(),
+    }];
+    assert_eq!(
+        Err(RollupClientError::MessageNotPending),
+        contract.rollup_cond_eq(vec![], vec![], actions)
+    );
+
+    // the head is moved after the cancelled messages
+    let actions = vec![HandleActionInput::Ack(vec![0])];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(3, contract.get_queue_bounds().unwrap().head);
+    assert_eq!(Ok(()), contract.cancel(3));
+    assert_eq!(4, contract.get_queue_bounds().unwrap().head);
+}
+
+#[ink::test]
+fn test_skip_expired_messages() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    let deadline = ink::env::block_number::<DefaultEnvironment>() + 1;
+    assert_eq!(Ok(0), contract.push_message(&0u8));
+    assert_eq!(Ok(1), contract.push_message_with_deadline(&1u8, deadline));
+    assert_eq!(Ok(2), contract.push_message(&2u8));
+
+    ink::env::test::advance_block::<DefaultEnvironment>();
+    ink::env::test::advance_block::<DefaultEnvironment>();
+    assert_eq!(Ok(()), contract.expire(1));
+
+    // the expired message is closed for all the readers
+    assert_eq!(Ok(None), RollupClient::get_message(&contract, 1));
+    let key = [b"q/".as_slice(), &1u32.encode()].concat();
+    assert_eq!(None, contract.get_value(key));
+    assert_eq!(
+        Ok(vec![(0, 0u8.encode()), (2, 2u8.encode())]),
+        contract.get_messages(0, 10)
+    );
+
+    // and the head is moved after it
+    let actions = vec![HandleActionInput::Ack(vec![0])];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(2, contract.get_queue_bounds().unwrap().head);
+}
+
+#[ink::test]
+fn test_ack_expired_message() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    let deadline = ink::env::block_number::<DefaultEnvironment>() + 1;
+    assert_eq!(Ok(0), contract.push_message_with_deadline(&0u8, deadline));
+    assert_eq!(Ok(1), contract.push_message(&1u8));
+
+    ink::env::test::advance_block::<DefaultEnvironment>();
+    ink::env::test::advance_block::<DefaultEnvironment>();
+
+    // the message acknowledged after its deadline is closed as expired, not answered
+    let actions = vec![HandleActionInput::Ack(vec![0])];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(1, contract.get_queue_bounds().unwrap().head);
+    assert_eq!(
+        Some(0u32.encode()),
+        contract.get_value(b"app/expired".to_vec())
+    );
+}
+
+#[ink::test]
+fn test_read_queue() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    for i in 0..5u16 {
+        contract.push_message(&i).unwrap();
+    }
+    let actions = vec![HandleActionInput::SetQueueHead(2)];
+    assert_eq!(contract.rollup_cond_eq(vec![], vec![], actions), Ok(()));
+
+    let bounds = contract.get_queue_bounds().unwrap();
+    assert_eq!(2, bounds.head);
+    assert_eq!(5, bounds.tail);
+
+    assert_eq!(Ok(None), RollupClient::get_message(&contract, 1));
+    assert_eq!(
+        Ok(Some(3u16.encode())),
+        RollupClient::get_message(&contract, 3)
+    );
+    assert_eq!(Ok(None), RollupClient::get_message(&contract, 5));
+
+    // the processed messages are skipped
+    assert_eq!(
+        Ok(vec![(2, 2u16.encode()), (3, 3u16.encode())]),
+        contract.get_messages(0, 2)
+    );
+    assert_eq!(
+        Ok(vec![(3, 3u16.encode()), (4, 4u16.encode())]),
+        contract.get_messages(3, 10)
+    );
+    assert_eq!(Ok(vec![]), contract.get_messages(5, 10));
+}
+
+#[ink::test]
+fn test_lease_limits() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+    for i in 0..(MAX_ACTIVE_LEASES + 1) {
+        contract.push_message(&i).unwrap();
+    }
+
+    // the admin shortens the leases
+    let limits = QueueLimits {
+        max_lease_blocks: Some(10),
+        ..Default::default()
+    };
+    assert_eq!(Ok(()), contract.set_queue_limits(limits));
+    let current_block = ink::env::block_number::<DefaultEnvironment>();
+    let lease = |id: u32, until_block: u32| {
+        vec![HandleActionInput::Lease {
+            from: id,
+            to: id + 1,
+            until_block,
+        }]
+    };
+    assert_eq!(
+        Err(RollupClientError::InvalidLeaseRange),
+        contract.rollup_cond_eq(vec![], vec![], lease(0, current_block + 11))
+    );
+
+    // the number of leases not expired is limited
+    for id in 0..MAX_ACTIVE_LEASES {
+        assert_eq!(
+            Ok(()),
+            contract.rollup_cond_eq(vec![], vec![], lease(id, current_block + 10))
+        );
+    }
+    assert_eq!(
+        Err(RollupClientError::TooManyLeases),
+        contract.rollup_cond_eq(vec![], vec![], lease(MAX_ACTIVE_LEASES, current_block + 10))
+    );
+
+    // the expired leases are forgotten
+    for _ in 0..11 {
+        ink::env::test::advance_block::<DefaultEnvironment>();
+    }
+    assert_eq!(
+        Ok(()),
+        contract.rollup_cond_eq(vec![], vec![], lease(MAX_ACTIVE_LEASES, current_block + 20))
+    );
+}
+
+#[ink::test]
+fn test_topic_actions() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    const URGENT: u32 = ink::selector_id!("urgent");
+    let deadline = ink::env::block_number::<DefaultEnvironment>() + 1;
+    assert_eq!(Ok(0), contract.push_message(&1u8));
+    change_caller(accounts.bob);
+    let mut urgent = TopicQueue::new(&mut contract, URGENT);
+    assert_eq!(Ok(0), urgent.push_message(&10u8));
+    assert_eq!(Ok(1), urgent.push_message_with_deadline(&11u8, deadline));
+    assert_eq!(Ok(2), urgent.push_message(&12u8));
+    assert_eq!(Ok(3), urgent.push_message(&13u8));
+    change_caller(accounts.alice);
+
+    // the messages of the topic are leased, answered and acknowledged in the queue of the topic
+    let until_block = ink::env::block_number::<DefaultEnvironment>() + 10;
+    let actions = vec![
+        HandleActionInput::TopicLease {
+            topic: URGENT,
+            from: 0,
+            to: 1,
+            until_block,
+        },
+        HandleActionInput::TopicReplyTo {
+            topic: URGENT,
+            request_id: 0,
+            payload: 20u8.encode(),
+        },
+        HandleActionInput::TopicAck {
+            topic: URGENT,
+            ids: vec![2],
+        },
+    ];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(
+        Some((10u8.encode(), 20u8.encode()).encode()),
+        contract.get_value(b"app/reply".to_vec())
+    );
+    let urgent = TopicQueue::new(&mut contract, URGENT);
+    assert!(urgent.get_message_lease(0).is_some());
+    assert_eq!(Some(MessageStatus::Answered), urgent.get_message_status(0));
+    assert_eq!(
+        Some(MessageStatus::Acknowledged),
+        urgent.get_message_status(2)
+    );
+
+    // the message with the same index in the default topic is not impacted
+    assert_eq!(None, MessageQueueReader::get_message_lease(&contract, 0));
+    assert_eq!(None, contract.get_message_status(0));
+
+    // the messages of the topic are expired and cancelled in the queue of the topic
+    ink::env::test::advance_block::<DefaultEnvironment>();
+    ink::env::test::advance_block::<DefaultEnvironment>();
+    let actions = vec![HandleActionInput::ExpireTopicMessages {
+        topic: URGENT,
+        ids: vec![1],
+    }];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(
+        Some(1u32.encode()),
+        contract.get_value(b"app/expired".to_vec())
+    );
+    assert_eq!(
+        Err(RollupClientError::MessageNotPending),
+        contract.cancel(3)
+    );
+    change_caller(accounts.bob);
+    assert_eq!(Ok(()), contract.cancel_topic_message(URGENT, 3));
+    assert_eq!(
+        Some(3u32.encode()),
+        contract.get_value(b"app/cancelled".to_vec())
+    );
+
+    // the head of the topic is moved after the closed messages
+    change_caller(accounts.alice);
+    let actions = vec![HandleActionInput::TopicAck {
+        topic: URGENT,
+        ids: vec![0],
+    }];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(
+        Ok(4),
+        contract.get_topic_queue_bounds(URGENT).map(|b| b.head)
+    );
+    assert_eq!(0, contract.get_queue_bounds().unwrap().head);
+}
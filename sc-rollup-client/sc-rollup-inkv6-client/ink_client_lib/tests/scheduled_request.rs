@@ -0,0 +1,190 @@
+mod contract;
+mod test_utils;
+
+use contract::test_contract::InkClient;
+use ink::env::DefaultEnvironment;
+use ink::scale::Encode;
+use inkv6_client_lib::traits::access_control::{AccessControl, AccessControlError};
+use inkv6_client_lib::traits::message_queue::{MessageQueue, QueueIndex};
+use inkv6_client_lib::traits::rollup_client::*;
+use inkv6_client_lib::traits::scheduled_request::{
+    BaseScheduledRequests, ScheduleStorage, ScheduledRequest, ScheduledRequests,
+    MAX_ACTIVE_SCHEDULES,
+};
+use inkv6_client_lib::traits::RollupClientError;
+use test_utils::{accounts, change_caller};
+
+fn advance_blocks(nb_blocks: u32) {
+    for _ in 0..nb_blocks {
+        ink::env::test::advance_block::<DefaultEnvironment>();
+    }
+}
+
+#[ink::test]
+fn test_invalid_schedule() {
+    let accounts = accounts();
+    let mut contract = InkClient::new(accounts.alice);
+
+    assert_eq!(
+        Err(RollupClientError::InvalidSchedule),
+        contract.schedule_request(vec![], 0, 10, 0)
+    );
+    assert_eq!(
+        Err(RollupClientError::InvalidSchedule),
+        contract.schedule_request(vec![], 0, 0, 2)
+    );
+    assert_eq!(
+        Err(RollupClientError::InvalidSchedule),
+        contract.schedule_request(vec![], 0, u32::MAX, 3)
+    );
+
+    for i in 0..MAX_ACTIVE_SCHEDULES {
+        assert_eq!(Ok(i), contract.schedule_request(vec![], 0, 10, 2));
+    }
+    assert_eq!(
+        Err(RollupClientError::TooManySchedules),
+        contract.schedule_request(vec![], 0, 10, 2)
+    );
+}
+
+#[ink::test]
+fn test_scheduled_requests() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    // 3 occurrences: blocks 2, 5 and 8
+    let payload = b"BTC/USD".to_vec();
+    assert_eq!(Ok(0), contract.schedule_request(payload.clone(), 2, 3, 3));
+
+    // not due yet
+    assert_eq!(Ok(0), contract.push_due_requests(10));
+    assert_eq!(0, contract.get_queue_bounds().unwrap().tail);
+
+    // anyone can push the due requests, they are sent on behalf of the contract
+    advance_blocks(2);
+    change_caller(accounts.bob);
+    assert_eq!(Ok(1), contract.push_due_requests(10));
+    assert_eq!(Ok(0), contract.push_due_requests(10));
+    let envelope = MessageQueueReader::get_message_envelope(&contract, 0)
+        .unwrap()
+        .unwrap();
+    assert_eq!(ink::env::address(), envelope.sender);
+    assert_eq!(
+        Err(RollupClientError::AccessControlError(
+            AccessControlError::MissingRole
+        )),
+        contract.cancel(0)
+    );
+    change_caller(accounts.alice);
+    assert_eq!(
+        Ok(Some(ScheduledRequest {
+            schedule_id: 0,
+            occurrence: 0,
+            payload: payload.clone(),
+        })),
+        MessageQueue::get_message(&contract, 0)
+    );
+
+    // the attestor reports the completion of the occurrence
+    let actions = vec![HandleActionInput::ReplyTo {
+        request_id: 0,
+        payload: 1u8.encode(),
+    }];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(1, contract.get_schedule(0).unwrap().completed);
+
+    // the occurrence of the block 5 is missed, the rollup transaction pushes the last one
+    advance_blocks(7);
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], vec![]));
+    assert_eq!(
+        Ok(Some(ScheduledRequest {
+            schedule_id: 0,
+            occurrence: 2,
+            payload,
+        })),
+        MessageQueue::get_message(&contract, 1)
+    );
+    let schedule = contract.get_schedule(0).unwrap();
+    assert_eq!(3, schedule.next_occurrence);
+    assert_eq!(1, schedule.missed);
+    assert_eq!(1, schedule.completed);
+
+    // all the occurrences have been pushed
+    advance_blocks(10);
+    assert_eq!(Ok(0), contract.push_due_requests(10));
+    assert_eq!(2, contract.get_queue_bounds().unwrap().tail);
+}
+
+#[ink::test]
+fn test_cancel_schedule() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(0), contract.schedule_request(vec![], 1, 1, 10));
+
+    // only the admin can cancel a schedule
+    change_caller(accounts.bob);
+    assert_eq!(
+        Err(RollupClientError::AccessControlError(
+            AccessControlError::MissingRole
+        )),
+        contract.cancel_schedule(0)
+    );
+
+    change_caller(accounts.alice);
+    assert_eq!(Ok(()), contract.cancel_schedule(0));
+    assert_eq!(None, contract.get_schedule(0));
+    assert_eq!(
+        Err(RollupClientError::ScheduleNotFound),
+        contract.cancel_schedule(0)
+    );
+
+    advance_blocks(2);
+    assert_eq!(Ok(0), contract.push_due_requests(10));
+}
+
+#[ink::test]
+fn test_occurrences_of_closed_messages() {
+    let accounts = accounts();
+    change_caller(accounts.alice);
+
+    let mut contract = InkClient::new(accounts.alice);
+    assert_eq!(Ok(()), contract.grant_role(ATTESTOR_ROLE, accounts.alice));
+
+    // 3 occurrences, one by block
+    let start = ink::env::block_number::<DefaultEnvironment>();
+    assert_eq!(Ok(0), contract.schedule_request(vec![], start, 1, 3));
+    for _ in 0..3 {
+        assert_eq!(Ok(1), contract.push_due_requests(10));
+        advance_blocks(1);
+    }
+    let queue = contract.queue_id();
+    let occurrence = |contract: &InkClient, id: QueueIndex| {
+        ScheduleStorage::get_storage(contract)
+            .occurrences
+            .get((queue, id))
+    };
+    assert_eq!(Some((0, 1)), occurrence(&contract, 1));
+
+    // the occurrence of a cancelled message is not kept
+    assert_eq!(Ok(()), contract.cancel(0));
+    assert_eq!(None, occurrence(&contract, 0));
+
+    // nor the occurrence of a message consumed without reply
+    let actions = vec![
+        HandleActionInput::SetQueueHead(2),
+        HandleActionInput::Reply(vec![]),
+    ];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(None, occurrence(&contract, 1));
+
+    // only the acknowledged occurrence is completed
+    let actions = vec![HandleActionInput::Ack(vec![2])];
+    assert_eq!(Ok(()), contract.rollup_cond_eq(vec![], vec![], actions));
+    assert_eq!(None, occurrence(&contract, 2));
+    assert_eq!(1, contract.get_schedule(0).unwrap().completed);
+}
@@ -1,3 +1,4 @@
+use crate::traits::channel::ChannelId;
 use ink::storage::Mapping;
 use ink::Address;
 
@@ -5,8 +6,9 @@ pub type RoleType = u32;
 
 pub const ADMIN_ROLE: RoleType = ink::selector_id!("ADMIN_ROLE");
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone)]
 #[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
 pub enum AccessControlError {
     InvalidCaller,
     MissingRole,
@@ -37,6 +39,8 @@ pub struct RoleRevoked {
 #[ink::storage_item]
 pub struct AccessControlData {
     pub roles: Mapping<(Address, RoleType), ()>,
+    /// roles granted in a channel other than the default one (the admins are shared by all the channels)
+    pub channel_roles: Mapping<(Address, ChannelId, RoleType), ()>,
 }
 
 #[macro_export]
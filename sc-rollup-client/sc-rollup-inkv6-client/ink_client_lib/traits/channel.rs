@@ -0,0 +1,361 @@
+use crate::only_role;
+use crate::traits::access_control::{
+    AccessControlData, AccessControlStorage, BaseAccessControl, RoleType, ADMIN_ROLE,
+};
+use crate::traits::dead_letter_queue::DeadLetter;
+use crate::traits::kv_store::{Key, KvStore, KvStoreData, KvStoreStorage, Value};
+use crate::traits::message_queue::{
+    MessageQueue, MessageQueueData, MessageQueueStorage, QueueId, QueueIndex,
+};
+use crate::traits::reply_quorum::CandidateReply;
+use crate::traits::rollup_client::{BaseRollupClient, HandleActionInput};
+use crate::traits::RollupClientError;
+use ink::prelude::vec::Vec;
+use ink::scale::Encode;
+use ink::Address;
+
+pub type ChannelId = u32;
+
+/// Channel used by the `RollupClient` messages. Its keys are not prefixed in the store.
+pub const DEFAULT_CHANNEL: ChannelId = 0;
+
+/// Prefix of the keys used by the other channels in the store
+pub const CHANNEL_PREFIX: &[u8] = b"c/";
+
+pub fn get_channel_key(channel: ChannelId, key: &Key) -> Key {
+    [CHANNEL_PREFIX, &channel.encode(), key].concat()
+}
+
+/// The roles of the default channel and the admin role are shared by all the channels
+fn is_shared_role(channel: ChannelId, role: RoleType) -> bool {
+    channel == DEFAULT_CHANNEL || role == ADMIN_ROLE
+}
+
+/// Return true if the account has the role in the given channel
+pub fn has_channel_role(
+    storage: &AccessControlData,
+    channel: ChannelId,
+    role: RoleType,
+    account: Address,
+) -> bool {
+    if is_shared_role(channel, role) {
+        storage.roles.contains((account, role))
+    } else {
+        storage.channel_roles.contains((account, channel, role))
+    }
+}
+
+/// Rollup client restricted to one channel: the key value store, the message queue
+/// and the attestors of this channel are isolated from the other channels.
+pub struct RollupChannel<'a, T: ?Sized> {
+    contract: &'a mut T,
+    channel: ChannelId,
+}
+
+impl<'a, T: ?Sized> RollupChannel<'a, T> {
+    pub fn new(contract: &'a mut T, channel: ChannelId) -> Self {
+        Self { contract, channel }
+    }
+}
+
+impl<T: KvStoreStorage + ?Sized> KvStoreStorage for RollupChannel<'_, T> {
+    fn get_storage(&self) -> &KvStoreData {
+        KvStoreStorage::get_storage(&*self.contract)
+    }
+
+    fn get_mut_storage(&mut self) -> &mut KvStoreData {
+        KvStoreStorage::get_mut_storage(&mut *self.contract)
+    }
+}
+
+impl<T: KvStore + ?Sized> KvStore for RollupChannel<'_, T> {
+    fn channel_id(&self) -> ChannelId {
+        self.channel
+    }
+
+    fn reserved_prefixes(&self) -> &[&[u8]] {
+        self.contract.reserved_prefixes()
+    }
+}
+
+impl<T: MessageQueueStorage + ?Sized> MessageQueueStorage for RollupChannel<'_, T> {
+    fn get_storage(&self) -> &MessageQueueData {
+        MessageQueueStorage::get_storage(&*self.contract)
+    }
+
+    fn get_mut_storage(&mut self) -> &mut MessageQueueData {
+        MessageQueueStorage::get_mut_storage(&mut *self.contract)
+    }
+}
+
+impl<T: MessageQueue + ?Sized> MessageQueue for RollupChannel<'_, T> {
+    fn on_message_closed(
+        &mut self,
+        queue: QueueId,
+        id: QueueIndex,
+    ) -> Result<(), RollupClientError> {
+        self.contract.on_message_closed(queue, id)
+    }
+}
+
+impl<T: AccessControlStorage + ?Sized> AccessControlStorage for RollupChannel<'_, T> {
+    fn get_storage(&self) -> &AccessControlData {
+        AccessControlStorage::get_storage(&*self.contract)
+    }
+
+    fn get_mut_storage(&mut self) -> &mut AccessControlData {
+        AccessControlStorage::get_mut_storage(&mut *self.contract)
+    }
+}
+
+/// The roles granted in the channel (the attestors for example) are isolated from the other channels
+impl<T: BaseAccessControl + ?Sized> BaseAccessControl for RollupChannel<'_, T> {
+    fn inner_has_role(&self, role: RoleType, account: Address) -> bool {
+        has_channel_role(
+            AccessControlStorage::get_storage(self),
+            self.channel,
+            role,
+            account,
+        )
+    }
+
+    fn inner_add_role(&mut self, role: RoleType, account: Address) {
+        let channel = self.channel;
+        let storage = AccessControlStorage::get_mut_storage(self);
+        if is_shared_role(channel, role) {
+            storage.roles.insert((account, role), &());
+        } else {
+            storage.channel_roles.insert((account, channel, role), &());
+        }
+    }
+
+    fn inner_remove_role(&mut self, role: RoleType, account: Address) {
+        let channel = self.channel;
+        let storage = AccessControlStorage::get_mut_storage(self);
+        if is_shared_role(channel, role) {
+            storage.roles.remove((account, role));
+        } else {
+            storage.channel_roles.remove((account, channel, role));
+        }
+    }
+}
+
+impl<T: BaseRollupClient + ?Sized> BaseRollupClient for RollupChannel<'_, T> {
+    fn keep_key_metadata(&self) -> bool {
+        self.contract.keep_key_metadata()
+    }
+
+    fn collect_reply(
+        &mut self,
+        queue: QueueId,
+        attestor: Address,
+        id: QueueIndex,
+        payload: Vec<u8>,
+    ) -> Result<Option<Vec<CandidateReply>>, RollupClientError> {
+        self.contract.collect_reply(queue, attestor, id, payload)
+    }
+
+    fn take_timed_out_replies(
+        &mut self,
+        queue: QueueId,
+        id: QueueIndex,
+    ) -> Result<Vec<CandidateReply>, RollupClientError> {
+        self.contract.take_timed_out_replies(queue, id)
+    }
+
+    fn on_message_answered(
+        &mut self,
+        queue: QueueId,
+        id: QueueIndex,
+        attestors: Vec<Address>,
+    ) -> Result<(), RollupClientError> {
+        self.contract.on_message_answered(queue, id, attestors)
+    }
+
+    fn on_messages_consumed(
+        &mut self,
+        queue: QueueId,
+        attestor: Address,
+        from: QueueIndex,
+        to: QueueIndex,
+    ) -> Result<(), RollupClientError> {
+        self.contract
+            .on_messages_consumed(queue, attestor, from, to)
+    }
+
+    fn on_reply_rejected(
+        &mut self,
+        channel: ChannelId,
+        letter: DeadLetter,
+    ) -> Result<(), RollupClientError> {
+        self.contract.on_reply_rejected(channel, letter)
+    }
+
+    fn callback_requester(&mut self, queue: QueueId, id: QueueIndex, response: &[u8]) -> bool {
+        self.contract.callback_requester(queue, id, response)
+    }
+
+    fn on_rollup_applied(&mut self, channel: ChannelId) -> Result<(), RollupClientError> {
+        self.contract.on_rollup_applied(channel)
+    }
+
+    fn attestor_role(&self) -> RoleType {
+        self.contract.attestor_role()
+    }
+
+    fn on_message_received(&mut self, action: Vec<u8>) -> Result<(), RollupClientError> {
+        if self.channel == DEFAULT_CHANNEL {
+            return self.contract.on_message_received(action);
+        }
+        self.contract
+            .on_channel_message_received(self.channel, action)
+    }
+
+    fn on_reply(&mut self, request: Vec<u8>, response: Vec<u8>) -> Result<(), RollupClientError> {
+        if self.channel == DEFAULT_CHANNEL {
+            return self.contract.on_reply(request, response);
+        }
+        self.contract
+            .on_channel_reply(self.channel, request, response)
+    }
+
+    fn aggregate(
+        &mut self,
+        channel: ChannelId,
+        replies: Vec<CandidateReply>,
+    ) -> Result<Vec<u8>, RollupClientError> {
+        self.contract.aggregate(channel, replies)
+    }
+
+    fn on_message_expired(
+        &mut self,
+        channel: ChannelId,
+        id: QueueIndex,
+        message: Vec<u8>,
+    ) -> Result<(), RollupClientError> {
+        self.contract.on_message_expired(channel, id, message)
+    }
+
+    fn on_message_cancelled(
+        &mut self,
+        channel: ChannelId,
+        id: QueueIndex,
+        message: Vec<u8>,
+    ) -> Result<(), RollupClientError> {
+        self.contract.on_message_cancelled(channel, id, message)
+    }
+
+    fn on_message_failed(
+        &mut self,
+        channel: ChannelId,
+        id: QueueIndex,
+        message: Vec<u8>,
+    ) -> Result<(), RollupClientError> {
+        self.contract.on_message_failed(channel, id, message)
+    }
+}
+
+#[ink::trait_definition]
+pub trait MultiChannelRollupClient {
+    #[ink(message)]
+    fn get_channel_value(&self, channel: ChannelId, key: Key) -> Option<Value>;
+
+    #[ink(message)]
+    fn has_channel_message(&self, channel: ChannelId) -> Result<bool, RollupClientError>;
+
+    #[ink(message)]
+    fn prune_channel_queue(
+        &mut self,
+        channel: ChannelId,
+        max: QueueIndex,
+    ) -> Result<QueueIndex, RollupClientError>;
+
+    #[ink(message)]
+    fn channel_rollup_cond_eq(
+        &mut self,
+        channel: ChannelId,
+        conditions: Vec<(Key, Option<Value>)>,
+        updates: Vec<(Key, Option<Value>)>,
+        actions: Vec<HandleActionInput>,
+    ) -> Result<(), RollupClientError>;
+
+    #[ink(message)]
+    fn has_channel_role(&self, channel: ChannelId, role: RoleType, account: Address) -> bool;
+
+    #[ink(message)]
+    fn grant_channel_role(
+        &mut self,
+        channel: ChannelId,
+        role: RoleType,
+        account: Address,
+    ) -> Result<(), RollupClientError>;
+
+    #[ink(message)]
+    fn revoke_channel_role(
+        &mut self,
+        channel: ChannelId,
+        role: RoleType,
+        account: Address,
+    ) -> Result<(), RollupClientError>;
+}
+
+pub trait BaseMultiChannelRollupClient: BaseRollupClient {
+    fn push_channel_message<M: ink::scale::Encode>(
+        &mut self,
+        channel: ChannelId,
+        data: &M,
+    ) -> Result<QueueIndex, RollupClientError> {
+        RollupChannel::new(self, channel).push_message(data)
+    }
+
+    fn inner_prune_channel_queue(
+        &mut self,
+        channel: ChannelId,
+        max: QueueIndex,
+    ) -> Result<QueueIndex, RollupClientError> {
+        RollupChannel::new(self, channel).prune_queue(max)
+    }
+
+    fn inner_channel_rollup_cond_eq(
+        &mut self,
+        channel: ChannelId,
+        conditions: Vec<(Key, Option<Value>)>,
+        updates: Vec<(Key, Option<Value>)>,
+        actions: Vec<HandleActionInput>,
+    ) -> Result<(), RollupClientError> {
+        RollupChannel::new(self, channel).inner_rollup_cond_eq(conditions, updates, actions)
+    }
+
+    fn inner_has_channel_role(&self, channel: ChannelId, role: RoleType, account: Address) -> bool {
+        has_channel_role(
+            AccessControlStorage::get_storage(self),
+            channel,
+            role,
+            account,
+        )
+    }
+
+    /// Grant the role in the channel (only an admin can do it)
+    fn inner_grant_channel_role(
+        &mut self,
+        channel: ChannelId,
+        role: RoleType,
+        account: Address,
+    ) -> Result<(), RollupClientError> {
+        only_role!(self, ADMIN_ROLE);
+        RollupChannel::new(self, channel).inner_grant_role_unchecked(role, account)?;
+        Ok(())
+    }
+
+    /// Revoke the role in the channel (only an admin can do it)
+    fn inner_revoke_channel_role(
+        &mut self,
+        channel: ChannelId,
+        role: RoleType,
+        account: Address,
+    ) -> Result<(), RollupClientError> {
+        only_role!(self, ADMIN_ROLE);
+        RollupChannel::new(self, channel).inner_revoke_role_unchecked(role, account)?;
+        Ok(())
+    }
+}
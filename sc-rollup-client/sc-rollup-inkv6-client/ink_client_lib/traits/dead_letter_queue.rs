@@ -0,0 +1,193 @@
+use crate::only_role;
+use crate::traits::access_control::{BaseAccessControl, ADMIN_ROLE};
+use crate::traits::channel::{ChannelId, RollupChannel, DEFAULT_CHANNEL};
+use crate::traits::kv_store::BlockNumber;
+use crate::traits::message_queue::{QueueId, QueueIndex};
+use crate::traits::rollup_client::{BaseRollupClient, MAX_MESSAGES_BY_PAGE};
+use crate::traits::RollupClientError;
+use ink::prelude::vec::Vec;
+use ink::storage::Mapping;
+use ink::Address;
+
+pub type DeadLetterId = u32;
+
+/// Reply rejected by the contract, kept until the admin retries or discards it
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct DeadLetter {
+    /// request answered by the reply (action `ReplyTo`)
+    pub request_id: Option<QueueIndex>,
+    /// queue of the request, the indexes restart from 0 in a new epoch
+    pub queue: QueueId,
+    /// encoded request, so the reply can be handled again once the request is deleted from the queue
+    pub request: Option<Vec<u8>>,
+    /// attestors who have sent the reply, paid if the reply is accepted later
+    pub attestors: Vec<Address>,
+    /// encoded reply
+    pub payload: Vec<u8>,
+    /// error returned by the contract
+    pub error: RollupClientError,
+    /// block when the reply has been rejected
+    pub block_number: BlockNumber,
+}
+
+#[derive(Default, Debug)]
+#[ink::storage_item]
+pub struct DeadLetterData {
+    pub letters: Mapping<(ChannelId, DeadLetterId), DeadLetter>,
+    pub next_id: Mapping<ChannelId, DeadLetterId>,
+}
+
+impl DeadLetterData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(
+        &mut self,
+        channel: ChannelId,
+        letter: &DeadLetter,
+    ) -> Result<DeadLetterId, RollupClientError> {
+        let id = self.next_id.get(channel).unwrap_or_default();
+        let next_id = id
+            .checked_add(1)
+            .ok_or(RollupClientError::QueueIndexOverflow)?;
+        self.letters.insert((channel, id), letter);
+        self.next_id.insert(channel, &next_id);
+        Ok(id)
+    }
+}
+
+pub trait DeadLetterStorage {
+    fn get_storage(&self) -> &DeadLetterData;
+    fn get_mut_storage(&mut self) -> &mut DeadLetterData;
+}
+
+/// Event emitted when a reply is moved to the dead letter queue
+#[ink::event]
+pub struct ReplyDeadLettered {
+    #[ink(topic)]
+    channel: ChannelId,
+    #[ink(topic)]
+    id: DeadLetterId,
+    error: RollupClientError,
+}
+
+/// Event emitted when a dead letter is retried successfully or discarded
+#[ink::event]
+pub struct DeadLetterRemoved {
+    #[ink(topic)]
+    channel: ChannelId,
+    #[ink(topic)]
+    id: DeadLetterId,
+    retried: bool,
+}
+
+#[ink::trait_definition]
+pub trait DeadLetterQueue {
+    #[ink(message)]
+    fn get_dead_letters(
+        &self,
+        channel: ChannelId,
+        from: DeadLetterId,
+        limit: DeadLetterId,
+    ) -> Vec<(DeadLetterId, DeadLetter)>;
+
+    #[ink(message)]
+    fn retry_dead_letter(
+        &mut self,
+        channel: ChannelId,
+        id: DeadLetterId,
+    ) -> Result<(), RollupClientError>;
+
+    #[ink(message)]
+    fn discard_dead_letter(
+        &mut self,
+        channel: ChannelId,
+        id: DeadLetterId,
+    ) -> Result<(), RollupClientError>;
+}
+
+pub trait BaseDeadLetterQueue: BaseRollupClient + DeadLetterStorage {
+    /// Save the reply rejected by the contract, so the rest of the batch is applied.
+    /// Call this method in the hook `on_reply_rejected` to use the dead letter queue.
+    fn push_dead_letter(
+        &mut self,
+        channel: ChannelId,
+        letter: DeadLetter,
+    ) -> Result<(), RollupClientError> {
+        let error = letter.error.clone();
+        let id = DeadLetterStorage::get_mut_storage(self).push(channel, &letter)?;
+        ::ink::env::emit_event(ReplyDeadLettered { channel, id, error });
+        Ok(())
+    }
+
+    /// Dead letters of the channel, from the given id (at most `MAX_MESSAGES_BY_PAGE` letters)
+    fn inner_get_dead_letters(
+        &self,
+        channel: ChannelId,
+        from: DeadLetterId,
+        limit: DeadLetterId,
+    ) -> Vec<(DeadLetterId, DeadLetter)> {
+        let storage = DeadLetterStorage::get_storage(self);
+        let to = from
+            .saturating_add(limit.min(MAX_MESSAGES_BY_PAGE))
+            .min(storage.next_id.get(channel).unwrap_or_default());
+        (from..to)
+            .filter_map(|id| {
+                storage
+                    .letters
+                    .get((channel, id))
+                    .map(|letter| (id, letter))
+            })
+            .collect()
+    }
+
+    /// Handle again the reply with the request saved in the dead letter (the request may have been processed since).
+    /// The dead letter is removed only if the contract accepts it.
+    fn inner_retry_dead_letter(
+        &mut self,
+        channel: ChannelId,
+        id: DeadLetterId,
+    ) -> Result<(), RollupClientError> {
+        only_role!(self, ADMIN_ROLE);
+        let letter = DeadLetterStorage::get_storage(self)
+            .letters
+            .get((channel, id))
+            .ok_or(RollupClientError::DeadLetterNotFound)?;
+        match channel {
+            DEFAULT_CHANNEL => self.retry_reply(letter)?,
+            _ => RollupChannel::new(self, channel).retry_reply(letter)?,
+        }
+        self.remove_dead_letter(channel, id, true);
+        Ok(())
+    }
+
+    fn inner_discard_dead_letter(
+        &mut self,
+        channel: ChannelId,
+        id: DeadLetterId,
+    ) -> Result<(), RollupClientError> {
+        only_role!(self, ADMIN_ROLE);
+        if !DeadLetterStorage::get_storage(self)
+            .letters
+            .contains((channel, id))
+        {
+            return Err(RollupClientError::DeadLetterNotFound);
+        }
+        self.remove_dead_letter(channel, id, false);
+        Ok(())
+    }
+
+    fn remove_dead_letter(&mut self, channel: ChannelId, id: DeadLetterId, retried: bool) {
+        DeadLetterStorage::get_mut_storage(self)
+            .letters
+            .remove((channel, id));
+        ::ink::env::emit_event(DeadLetterRemoved {
+            channel,
+            id,
+            retried,
+        });
+    }
+}
@@ -0,0 +1,120 @@
+use crate::only_role;
+use crate::traits::access_control::BaseAccessControl;
+use crate::traits::channel::ChannelId;
+use crate::traits::message_queue::{PushOptions, QueueIndex};
+use crate::traits::rollup_client::BaseRollupClient;
+use crate::traits::RollupClientError;
+use ink::prelude::vec::Vec;
+use ink::storage::Mapping;
+
+/// x25519 public key
+pub type PublicKey = [u8; 32];
+pub type EncryptionKeyId = u32;
+/// ChaCha20-Poly1305 nonce
+pub type Nonce = [u8; 12];
+
+/// Public key registered by the worker; the payloads are encrypted with this key
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct WorkerPublicKey {
+    /// incremented each time the worker registers a new key
+    pub id: EncryptionKeyId,
+    pub public_key: PublicKey,
+}
+
+/// Payload encrypted for the worker (x25519 + ChaCha20-Poly1305).
+/// The symmetric key is derived from the ephemeral key of the sender and the public key of the worker.
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub struct EncryptedPayload {
+    /// id of the worker key used to encrypt the payload
+    pub key_id: EncryptionKeyId,
+    pub ephemeral_public_key: PublicKey,
+    pub nonce: Nonce,
+    /// encrypted payload followed by the authentication tag
+    pub ciphertext: Vec<u8>,
+}
+
+#[derive(Default, Debug)]
+#[ink::storage_item]
+pub struct EncryptionKeyData {
+    pub worker_keys: Mapping<ChannelId, WorkerPublicKey>,
+}
+
+impl EncryptionKeyData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+pub trait EncryptionKeyStorage {
+    fn get_storage(&self) -> &EncryptionKeyData;
+    fn get_mut_storage(&mut self) -> &mut EncryptionKeyData;
+}
+
+/// Event emitted when the worker registers a new public key
+#[ink::event]
+pub struct WorkerPublicKeySet {
+    #[ink(topic)]
+    channel: ChannelId,
+    id: EncryptionKeyId,
+    public_key: PublicKey,
+}
+
+#[ink::trait_definition]
+pub trait EncryptedMessageQueue {
+    #[ink(message)]
+    fn get_worker_public_key(&self) -> Option<WorkerPublicKey>;
+
+    #[ink(message)]
+    fn set_worker_public_key(&mut self, public_key: PublicKey) -> Result<(), RollupClientError>;
+}
+
+pub trait BaseEncryptedMessageQueue: BaseRollupClient + EncryptionKeyStorage {
+    /// Push a payload encrypted with the current key of the worker.
+    /// Only the ciphertext is saved in the storage and emitted in the event `MessageQueued`.
+    fn push_encrypted_message(
+        &mut self,
+        payload: &EncryptedPayload,
+        options: PushOptions,
+    ) -> Result<QueueIndex, RollupClientError> {
+        match self.inner_get_worker_public_key() {
+            Some(key) if key.id == payload.key_id => {}
+            _ => return Err(RollupClientError::UnknownEncryptionKey),
+        }
+        self.push_message_with_options(payload, options)
+    }
+
+    fn inner_get_worker_public_key(&self) -> Option<WorkerPublicKey> {
+        EncryptionKeyStorage::get_storage(self)
+            .worker_keys
+            .get(self.channel_id())
+    }
+
+    /// Register the public key of the worker (only an attestor of the channel can do it).
+    /// The payloads encrypted with the previous key are rejected by `push_encrypted_message`.
+    fn inner_set_worker_public_key(
+        &mut self,
+        public_key: PublicKey,
+    ) -> Result<(), RollupClientError> {
+        let role = self.attestor_role();
+        only_role!(self, role);
+
+        let channel = self.channel_id();
+        let id = match self.inner_get_worker_public_key() {
+            Some(key) => key.id.wrapping_add(1),
+            None => 0,
+        };
+        EncryptionKeyStorage::get_mut_storage(self)
+            .worker_keys
+            .insert(channel, &WorkerPublicKey { id, public_key });
+
+        ::ink::env::emit_event(WorkerPublicKeySet {
+            channel,
+            id,
+            public_key,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,360 @@
+use crate::only_role;
+use crate::traits::access_control::{BaseAccessControl, ADMIN_ROLE};
+use crate::traits::message_queue::{PushOptions, QueueId, QueueIndex, Topic, TypeTag};
+use crate::traits::rollup_client::BaseRollupClient;
+use crate::traits::RollupClientError;
+use ink::storage::Mapping;
+use ink::{Address, U256};
+
+pub type Fee = U256;
+
+/// Messages concerned by a fee
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub enum FeeTarget {
+    /// messages pushed in the given topic
+    Topic(Topic),
+    /// messages pushed with the given type. This fee takes precedence over the fee of the topic.
+    TypeTag(TypeTag),
+}
+
+/// Fee paid by the sender of a message, kept by the contract until the reply is accepted
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct Escrow {
+    /// refunded to this address if the message is closed without reply (cancelled, expired, acknowledged or skipped)
+    pub payer: Address,
+    pub amount: Fee,
+}
+
+/// Messages from `from` (included) to `to` (excluded) consumed by an attestor with the action `SetQueueHead`
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct ConsumedRange {
+    pub from: QueueIndex,
+    pub to: QueueIndex,
+    pub attestor: Address,
+}
+
+#[derive(Default, Debug)]
+#[ink::storage_item]
+pub struct FeeEscrowData {
+    pub fees: Mapping<FeeTarget, Fee>,
+    pub escrows: Mapping<(QueueId, QueueIndex), Escrow>,
+    /// address receiving the fees of the messages answered by the attestor
+    pub payout_addresses: Mapping<Address, Address>,
+    /// ranges of messages consumed and not deleted yet, by index of their first message
+    pub consumed: Mapping<(QueueId, QueueIndex), ConsumedRange>,
+    /// range of the messages being deleted from the queue
+    pub deleted_range: Mapping<QueueId, ConsumedRange>,
+    /// fees which couldn't be transferred, claimed by their owner with `claim_fees`
+    pub claimable: Mapping<Address, Fee>,
+}
+
+impl FeeEscrowData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+pub trait FeeEscrowStorage {
+    fn get_storage(&self) -> &FeeEscrowData;
+    fn get_mut_storage(&mut self) -> &mut FeeEscrowData;
+}
+
+/// Event emitted when the fee of a message is kept by the contract
+#[ink::event]
+pub struct FeeEscrowed {
+    #[ink(topic)]
+    id: QueueIndex,
+    payer: Address,
+    amount: Fee,
+}
+
+/// Event emitted when the fee of a message is paid to the attestor
+#[ink::event]
+pub struct FeePaid {
+    #[ink(topic)]
+    id: QueueIndex,
+    #[ink(topic)]
+    attestor: Address,
+    payee: Address,
+    amount: Fee,
+}
+
+/// Event emitted when the fee of a message is refunded to the sender
+#[ink::event]
+pub struct FeeRefunded {
+    #[ink(topic)]
+    id: QueueIndex,
+    payer: Address,
+    amount: Fee,
+}
+
+/// Event emitted when a fee can't be transferred: the amount is kept until its owner claims it
+#[ink::event]
+pub struct FeeClaimable {
+    #[ink(topic)]
+    id: QueueIndex,
+    #[ink(topic)]
+    account: Address,
+    amount: Fee,
+}
+
+/// Event emitted when an account claims the fees which couldn't be transferred
+#[ink::event]
+pub struct FeeClaimed {
+    #[ink(topic)]
+    account: Address,
+    amount: Fee,
+}
+
+#[ink::trait_definition]
+pub trait FeeEscrow {
+    #[ink(message)]
+    fn get_fee(&self, target: FeeTarget) -> Option<Fee>;
+
+    #[ink(message)]
+    fn set_fee(&mut self, target: FeeTarget, fee: Option<Fee>) -> Result<(), RollupClientError>;
+
+    #[ink(message)]
+    fn get_escrow(&self, id: QueueIndex) -> Option<Escrow>;
+
+    #[ink(message)]
+    fn set_payout_address(&mut self, payout_address: Option<Address>);
+
+    #[ink(message)]
+    fn get_claimable_fees(&self, account: Address) -> Fee;
+
+    #[ink(message)]
+    fn claim_fees(&mut self) -> Result<Fee, RollupClientError>;
+}
+
+pub trait BaseFeeEscrow: BaseRollupClient + FeeEscrowStorage {
+    /// Push a message and keep the transferred value until the message is answered.
+    /// The transferred value must cover the fee of the type of the message (or of the topic).
+    fn push_paid_message<M: ink::scale::Encode>(
+        &mut self,
+        data: &M,
+        options: PushOptions,
+    ) -> Result<QueueIndex, RollupClientError> {
+        let fee = options
+            .type_tag
+            .and_then(|type_tag| self.inner_get_fee(FeeTarget::TypeTag(type_tag)))
+            .or_else(|| self.inner_get_fee(FeeTarget::Topic(self.topic())))
+            .unwrap_or_default();
+        let amount = ::ink::env::transferred_value();
+        if amount < fee {
+            return Err(RollupClientError::InsufficientFee);
+        }
+
+        let id = self.push_message_with_options(data, options)?;
+        if amount > U256::zero() {
+            let escrow = Escrow {
+                payer: ::ink::env::caller(),
+                amount,
+            };
+            let queue = self.queue_id();
+            FeeEscrowStorage::get_mut_storage(self)
+                .escrows
+                .insert((queue, id), &escrow);
+            ::ink::env::emit_event(FeeEscrowed {
+                id,
+                payer: escrow.payer,
+                amount,
+            });
+        }
+        Ok(id)
+    }
+
+    /// Share the fee kept for the message between the payout addresses of the attestors who have sent the reply
+    /// or acknowledged the message (the remainder goes to the first one). The fee is refunded if there is no attestor.
+    /// A share which can't be transferred is kept for its payee (`claim_fees`), so the batch is never reverted.
+    /// Nothing to do if there is no fee.
+    fn release_fee(
+        &mut self,
+        queue: QueueId,
+        id: QueueIndex,
+        attestors: &[Address],
+    ) -> Result<(), RollupClientError> {
+        let Some(escrow) = FeeEscrowStorage::get_storage(self).escrows.get((queue, id)) else {
+            return Ok(());
+        };
+        let nb_attestors = U256::from(attestors.len());
+        let (Some(share), Some(remainder)) = (
+            escrow.amount.checked_div(nb_attestors),
+            escrow.amount.checked_rem(nb_attestors),
+        ) else {
+            return self.refund_fee(queue, id);
+        };
+
+        for (index, attestor) in attestors.iter().enumerate() {
+            let amount = match index {
+                0 => share.saturating_add(remainder),
+                _ => share,
+            };
+            if amount.is_zero() {
+                continue;
+            }
+            let payee = FeeEscrowStorage::get_storage(self)
+                .payout_addresses
+                .get(attestor)
+                .unwrap_or(*attestor);
+            if ::ink::env::transfer::<::ink::env::DefaultEnvironment>(payee, amount).is_err() {
+                self.credit_fee(id, payee, amount);
+                continue;
+            }
+            ::ink::env::emit_event(FeePaid {
+                id,
+                attestor: *attestor,
+                payee,
+                amount,
+            });
+        }
+        FeeEscrowStorage::get_mut_storage(self)
+            .escrows
+            .remove((queue, id));
+        Ok(())
+    }
+
+    /// Refund the fee kept for the message to its sender (nothing to do if there is no fee).
+    /// The fee is kept for the sender (`claim_fees`) if the transfer fails.
+    fn refund_fee(&mut self, queue: QueueId, id: QueueIndex) -> Result<(), RollupClientError> {
+        let Some(escrow) = FeeEscrowStorage::get_mut_storage(self)
+            .escrows
+            .take((queue, id))
+        else {
+            return Ok(());
+        };
+        if ::ink::env::transfer::<::ink::env::DefaultEnvironment>(escrow.payer, escrow.amount)
+            .is_err()
+        {
+            self.credit_fee(id, escrow.payer, escrow.amount);
+            return Ok(());
+        }
+        ::ink::env::emit_event(FeeRefunded {
+            id,
+            payer: escrow.payer,
+            amount: escrow.amount,
+        });
+        Ok(())
+    }
+
+    /// Settle the fee of a message closed or deleted without accepted reply: the fee is paid to the attestor
+    /// who has consumed the message (action `SetQueueHead`), refunded to the sender otherwise.
+    /// Call this method in the hook `on_message_closed`.
+    fn settle_fee(&mut self, queue: QueueId, id: QueueIndex) -> Result<(), RollupClientError> {
+        match self.take_consumer(queue, id) {
+            Some(attestor) => self.release_fee(queue, id, &[attestor]),
+            None => self.refund_fee(queue, id),
+        }
+    }
+
+    /// Keep the attestor who has consumed the messages from `from` (included) to `to` (excluded),
+    /// so their fees are paid to him when they are deleted. Call this method in the hook `on_messages_consumed`.
+    fn record_consumer(
+        &mut self,
+        queue: QueueId,
+        attestor: Address,
+        from: QueueIndex,
+        to: QueueIndex,
+    ) {
+        let range = ConsumedRange { from, to, attestor };
+        FeeEscrowStorage::get_mut_storage(self)
+            .consumed
+            .insert((queue, from), &range);
+    }
+
+    /// Attestor who has consumed the message deleted from the queue.
+    /// The messages are deleted in order, so the ranges are read one after the other.
+    fn take_consumer(&mut self, queue: QueueId, id: QueueIndex) -> Option<Address> {
+        let storage = FeeEscrowStorage::get_mut_storage(self);
+        if let Some(range) = storage.consumed.take((queue, id)) {
+            storage.deleted_range.insert(queue, &range);
+        }
+        let range = storage.deleted_range.get(queue)?;
+        if id.saturating_add(1) >= range.to {
+            storage.deleted_range.remove(queue);
+        }
+        (range.from <= id && id < range.to).then_some(range.attestor)
+    }
+
+    /// Keep the fee which can't be transferred until its owner claims it
+    fn credit_fee(&mut self, id: QueueIndex, account: Address, amount: Fee) {
+        let claimable = &mut FeeEscrowStorage::get_mut_storage(self).claimable;
+        let balance = claimable.get(account).unwrap_or_default();
+        claimable.insert(account, &balance.saturating_add(amount));
+        ::ink::env::emit_event(FeeClaimable {
+            id,
+            account,
+            amount,
+        });
+    }
+
+    fn inner_get_claimable_fees(&self, account: Address) -> Fee {
+        FeeEscrowStorage::get_storage(self)
+            .claimable
+            .get(account)
+            .unwrap_or_default()
+    }
+
+    /// Transfer to the caller the fees which couldn't be transferred before. Return the amount transferred.
+    fn inner_claim_fees(&mut self) -> Result<Fee, RollupClientError> {
+        let caller = ::ink::env::caller();
+        let amount = self.inner_get_claimable_fees(caller);
+        if amount.is_zero() {
+            return Ok(amount);
+        }
+        ::ink::env::transfer::<::ink::env::DefaultEnvironment>(caller, amount)
+            .map_err(|_| RollupClientError::TransferFailed)?;
+        FeeEscrowStorage::get_mut_storage(self)
+            .claimable
+            .remove(caller);
+        ::ink::env::emit_event(FeeClaimed {
+            account: caller,
+            amount,
+        });
+        Ok(amount)
+    }
+
+    fn inner_get_fee(&self, target: FeeTarget) -> Option<Fee> {
+        FeeEscrowStorage::get_storage(self).fees.get(target)
+    }
+
+    fn inner_set_fee(
+        &mut self,
+        target: FeeTarget,
+        fee: Option<Fee>,
+    ) -> Result<(), RollupClientError> {
+        only_role!(self, ADMIN_ROLE);
+        let fees = &mut FeeEscrowStorage::get_mut_storage(self).fees;
+        match fee {
+            Some(fee) => {
+                fees.insert(target, &fee);
+            }
+            None => fees.remove(target),
+        }
+        Ok(())
+    }
+
+    fn inner_get_escrow(&self, id: QueueIndex) -> Option<Escrow> {
+        FeeEscrowStorage::get_storage(self)
+            .escrows
+            .get((self.queue_id(), id))
+    }
+
+    /// Set the address receiving the fees of the messages answered by the caller (the caller by default)
+    fn inner_set_payout_address(&mut self, payout_address: Option<Address>) {
+        let caller = ::ink::env::caller();
+        let payout_addresses = &mut FeeEscrowStorage::get_mut_storage(self).payout_addresses;
+        match payout_address {
+            Some(address) => {
+                payout_addresses.insert(caller, &address);
+            }
+            None => payout_addresses.remove(caller),
+        }
+    }
+}
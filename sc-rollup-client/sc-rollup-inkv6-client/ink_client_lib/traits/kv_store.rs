@@ -1,13 +1,95 @@
+use crate::only_role;
+use crate::traits::access_control::{BaseAccessControl, ADMIN_ROLE};
+use crate::traits::channel::{get_channel_key, ChannelId, CHANNEL_PREFIX, DEFAULT_CHANNEL};
+use crate::traits::message_queue::QUEUE_PREFIX;
+use crate::traits::RollupClientError;
+use ink::env::{DefaultEnvironment, Environment};
 use ink::prelude::vec::Vec;
-use ink::storage::Mapping;
+use ink::scale::Encode;
+use ink::storage::{Lazy, Mapping};
+use ink::Address;
 
 pub type Key = Vec<u8>;
 pub type Value = Vec<u8>;
+pub type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+pub type Timestamp = <DefaultEnvironment as Environment>::Timestamp;
+pub type BatchSequence = u64;
+
+/// Prefixes of the keys managed by the library. These keys can only be modified via the actions.
+pub const RESERVED_PREFIXES: &[&[u8]] = &[QUEUE_PREFIX, CHANNEL_PREFIX];
+
+/// Limits checked when a value is saved in the key value store. No limit by default.
+/// Only the key value store is limited: the message queue and the data of the other features
+/// (key metadata, dead letters, pending replies, ...) are saved in their own storage.
+#[derive(Default, Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct KvStoreLimits {
+    /// max size of a key
+    pub max_key_size: Option<u32>,
+    /// max size of a value
+    pub max_value_size: Option<u32>,
+    /// max number of bytes (keys and encoded values) saved in the store
+    pub max_total_size: Option<u64>,
+}
+
+/// Number of keys and bytes (keys and encoded values) saved in the key value store
+#[derive(Default, Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct KvStoreUsage {
+    pub nb_keys: u32,
+    pub nb_bytes: u64,
+}
+
+impl KvStoreUsage {
+    fn update(&mut self, old_size: Option<u64>, new_size: Option<u64>) {
+        match (old_size, new_size) {
+            (None, Some(_)) => self.nb_keys = self.nb_keys.saturating_add(1),
+            (Some(_), None) => self.nb_keys = self.nb_keys.saturating_sub(1),
+            _ => {}
+        }
+        // the data saved before the usage was tracked is not counted
+        self.nb_bytes = self
+            .nb_bytes
+            .saturating_sub(old_size.unwrap_or_default())
+            .saturating_add(new_size.unwrap_or_default());
+    }
+}
+
+/// Last modification of a key by the rollup
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct KeyMetadata {
+    /// block when the key has been modified
+    pub block_number: BlockNumber,
+    /// timestamp when the key has been modified
+    pub timestamp: Timestamp,
+    /// attestor who has modified the key
+    pub attestor: Address,
+    /// sequence of the rollup transaction which has modified the key
+    pub batch_sequence: BatchSequence,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub struct KvStoreUsageReport {
+    pub total: KvStoreUsage,
+    /// usage by reserved prefix (except `q/`, the queue is not saved in the key value store).
+    /// The empty prefix is used for the other keys.
+    pub by_prefix: Vec<(Key, KvStoreUsage)>,
+}
 
 #[derive(Default, Debug)]
 #[ink::storage_item]
 pub struct KvStoreData {
     pub kv_store: Mapping<Key, Value>,
+    pub limits: Lazy<KvStoreLimits>,
+    pub total_usage: Lazy<KvStoreUsage>,
+    pub usage: Mapping<Key, KvStoreUsage>,
+    pub metadata: Mapping<Key, KeyMetadata>,
+    pub batch_sequence: Lazy<BatchSequence>,
 }
 
 impl KvStoreData {
@@ -22,16 +104,196 @@ pub trait KvStoreStorage {
 }
 
 pub trait KvStore: KvStoreStorage {
+    /// Channel used to isolate the data. The keys of the other channels are prefixed in the store.
+    fn channel_id(&self) -> ChannelId {
+        DEFAULT_CHANNEL
+    }
+
     fn inner_get_value(&self, key: &Key) -> Option<Value> {
-        self.get_storage().kv_store.get(key)
+        self.inner_get_channel_value(self.channel_id(), key)
+    }
+
+    fn inner_get_channel_value(&self, channel: ChannelId, key: &Key) -> Option<Value> {
+        match channel {
+            DEFAULT_CHANNEL => self.get_storage().kv_store.get(key),
+            _ => self
+                .get_storage()
+                .kv_store
+                .get(get_channel_key(channel, key)),
+        }
     }
 
-    fn inner_set_value(&mut self, key: &Key, value: Option<&Value>) {
+    fn inner_set_value(
+        &mut self,
+        key: &Key,
+        value: Option<&Value>,
+    ) -> Result<(), RollupClientError> {
+        let channel_key;
+        let key = match self.channel_id() {
+            DEFAULT_CHANNEL => key,
+            channel => {
+                channel_key = get_channel_key(channel, key);
+                &channel_key
+            }
+        };
+        self.update_kv_store_usage(key, value)?;
         match value {
             None => self.get_mut_storage().kv_store.remove(key),
             Some(v) => {
                 self.get_mut_storage().kv_store.insert(key, v);
             }
         }
+        Ok(())
+    }
+
+    fn inner_get_value_metadata(&self, key: &Key) -> Option<KeyMetadata> {
+        match self.channel_id() {
+            DEFAULT_CHANNEL => self.get_storage().metadata.get(key),
+            channel => self
+                .get_storage()
+                .metadata
+                .get(get_channel_key(channel, key)),
+        }
+    }
+
+    fn inner_set_value_metadata(&mut self, key: &Key, metadata: &KeyMetadata) {
+        match self.channel_id() {
+            DEFAULT_CHANNEL => self.get_mut_storage().metadata.insert(key, metadata),
+            channel => self
+                .get_mut_storage()
+                .metadata
+                .insert(get_channel_key(channel, key), metadata),
+        };
+    }
+
+    /// Check the limits and update the storage usage before saving the value
+    fn update_kv_store_usage(
+        &mut self,
+        key: &Key,
+        value: Option<&Value>,
+    ) -> Result<(), RollupClientError> {
+        let storage = self.get_storage();
+        let limits = storage.limits.get().unwrap_or_default();
+
+        let new_size = match value {
+            Some(v) => {
+                if limits
+                    .max_key_size
+                    .is_some_and(|max| key.len() > max as usize)
+                {
+                    return Err(RollupClientError::KeyTooLarge);
+                }
+                if limits
+                    .max_value_size
+                    .is_some_and(|max| v.len() > max as usize)
+                {
+                    return Err(RollupClientError::ValueTooLarge);
+                }
+                Some(key.len() as u64 + v.encoded_size() as u64)
+            }
+            None => None,
+        };
+        let old_size = storage
+            .kv_store
+            .size(key)
+            .map(|size| key.len() as u64 + size as u64);
+
+        if old_size.is_none() && new_size.is_none() {
+            // nothing to do
+            return Ok(());
+        }
+
+        let mut total_usage = storage.total_usage.get().unwrap_or_default();
+        total_usage.update(old_size, new_size);
+        if new_size > old_size
+            && limits
+                .max_total_size
+                .is_some_and(|max| total_usage.nb_bytes > max)
+        {
+            return Err(RollupClientError::StorageQuotaExceeded);
+        }
+
+        let prefix = self.get_usage_prefix(key);
+        let mut usage = self.get_storage().usage.get(&prefix).unwrap_or_default();
+        usage.update(old_size, new_size);
+
+        let storage = self.get_mut_storage();
+        storage.total_usage.set(&total_usage);
+        storage.usage.insert(&prefix, &usage);
+        Ok(())
+    }
+
+    /// Prefix used to group the storage usage of the given key
+    fn get_usage_prefix(&self, key: &Key) -> Key {
+        RESERVED_PREFIXES
+            .iter()
+            .chain(self.reserved_prefixes())
+            .find(|prefix| key.starts_with(prefix))
+            .map(|prefix| prefix.to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Prefixes of the keys managed by the contract itself.
+    /// Override this method to prevent the attestors to read or write these keys in the conditions and the updates.
+    fn reserved_prefixes(&self) -> &[&[u8]] {
+        &[]
+    }
+
+    fn is_reserved_key(&self, key: &Key) -> bool {
+        RESERVED_PREFIXES
+            .iter()
+            .chain(self.reserved_prefixes())
+            .any(|prefix| key.starts_with(prefix))
+    }
+}
+
+#[ink::trait_definition]
+pub trait KvStoreQuota {
+    #[ink(message)]
+    fn kv_store_usage(&self) -> KvStoreUsageReport;
+
+    #[ink(message)]
+    fn get_kv_store_limits(&self) -> KvStoreLimits;
+
+    #[ink(message)]
+    fn set_kv_store_limits(&mut self, limits: KvStoreLimits) -> Result<(), RollupClientError>;
+}
+
+pub trait BaseKvStoreQuota: KvStore + BaseAccessControl {
+    /// Usage of the key value store only, see `queue_stats` for the message queue
+    fn inner_kv_store_usage(&self) -> KvStoreUsageReport {
+        let storage = KvStoreStorage::get_storage(self);
+        let by_prefix = RESERVED_PREFIXES
+            .iter()
+            .filter(|prefix| **prefix != QUEUE_PREFIX)
+            .chain(self.reserved_prefixes())
+            .chain([&[][..]].iter())
+            .map(|prefix| {
+                let prefix = prefix.to_vec();
+                let usage = storage.usage.get(&prefix).unwrap_or_default();
+                (prefix, usage)
+            })
+            .collect();
+
+        KvStoreUsageReport {
+            total: storage.total_usage.get().unwrap_or_default(),
+            by_prefix,
+        }
+    }
+
+    fn inner_get_kv_store_limits(&self) -> KvStoreLimits {
+        KvStoreStorage::get_storage(self)
+            .limits
+            .get()
+            .unwrap_or_default()
+    }
+
+    fn inner_set_kv_store_limits(
+        &mut self,
+        limits: KvStoreLimits,
+    ) -> Result<(), RollupClientError> {
+        only_role!(self, ADMIN_ROLE);
+        KvStoreStorage::get_mut_storage(self).limits.set(&limits);
+        Ok(())
     }
 }
@@ -1,11 +1,44 @@
-use crate::traits::kv_store::KvStore;
+use crate::only_role;
+use crate::traits::access_control::{
+    AccessControlData, AccessControlStorage, BaseAccessControl, RoleType, ADMIN_ROLE,
+};
+use crate::traits::channel::{ChannelId, RollupChannel};
+use crate::traits::dead_letter_queue::DeadLetter;
+use crate::traits::kv_store::{
+    BlockNumber, Key, KvStore, KvStoreData, KvStoreStorage, Timestamp, Value,
+};
+use crate::traits::reply_quorum::CandidateReply;
+use crate::traits::rollup_client::BaseRollupClient;
 use crate::traits::RollupClientError;
 use ink::prelude::vec::Vec;
-use ink::scale::{Decode, Encode};
+use ink::scale::{Decode, DecodeAll, Encode};
+use ink::storage::{Lazy, Mapping};
+use ink::Address;
 
 pub type QueueIndex = u32;
+pub type TypeTag = u32;
+/// Key identifying a logical request, used to not push the same request twice
+pub type DedupKey = Vec<u8>;
+/// Lane of the message queue. Use `ink::selector_id!("name")` to name a topic.
+pub type Topic = u32;
+/// Number of times the indexes of the queue have been reset
+pub type QueueEpoch = u32;
 
-const QUEUE_PREFIX: &[u8] = b"q/";
+/// Topic used by default. Its queue is the queue of the channel.
+pub const DEFAULT_TOPIC: Topic = 0;
+
+/// Prefix of the keys used to read the queue with the key value store
+pub const QUEUE_PREFIX: &[u8] = b"q/";
+/// Max number of processed messages deleted when the head of the queue is moved
+pub const MAX_PRUNED_MESSAGES_ON_POP: QueueIndex = 10;
+/// Max number of messages already closed (acknowledged, cancelled or expired) skipped when the head of the queue is processed
+pub const MAX_SKIPPED_MESSAGES_ON_POP: QueueIndex = 50;
+/// Max number of messages leased in one action
+pub const MAX_LEASED_MESSAGES: QueueIndex = 100;
+/// Max number of leases not expired in each queue
+pub const MAX_ACTIVE_LEASES: u32 = 20;
+/// Max duration of a lease (in blocks) if the admin has not set `QueueLimits::max_lease_blocks`
+pub const DEFAULT_MAX_LEASE_BLOCKS: BlockNumber = 600;
 const QUEUE_HEAD_KEY: &[u8] = b"_head";
 const QUEUE_TAIL_KEY: &[u8] = b"_tail";
 
@@ -28,8 +61,8 @@ macro_rules! get_head_key {
 }
 
 macro_rules! get_queue_index {
-    ($kv:ident, $key:ident) => {{
-        match $kv.inner_get_value(&$key) {
+    ($kv:ident, $channel:ident, $key:ident) => {{
+        match $kv.inner_get_channel_value($channel, &$key) {
             Some(v) => QueueIndex::decode(&mut v.as_slice())
                 .map_err(|_| RollupClientError::FailedToDecode)?,
             _ => 0,
@@ -37,6 +70,216 @@ macro_rules! get_queue_index {
     }};
 }
 
+/// Queue of a topic in a channel. The indexes restart from 0 in a new epoch.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct QueueId {
+    pub channel: ChannelId,
+    pub topic: Topic,
+    pub epoch: QueueEpoch,
+}
+
+impl QueueId {
+    /// Return true if the queue may still be saved in the key value store (previous layout)
+    fn is_legacy(&self) -> bool {
+        self.topic == DEFAULT_TOPIC && self.epoch == 0
+    }
+}
+
+/// Head and tail of the queue, read and written together
+#[derive(Default, Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct QueueBounds {
+    /// index of the next message to process
+    pub head: QueueIndex,
+    /// index of the next message to push
+    pub tail: QueueIndex,
+    /// the processed messages before this index have been deleted
+    pub pruned_to: QueueIndex,
+}
+
+/// Information saved by the library when a message is pushed in the queue
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct MessageEnvelope {
+    /// account who has pushed the message
+    pub sender: Address,
+    /// block when the message has been pushed
+    pub block_number: BlockNumber,
+    /// timestamp when the message has been pushed
+    pub timestamp: Timestamp,
+    /// type of the message, defined by the application
+    pub type_tag: Option<TypeTag>,
+    /// the message expires after this block
+    pub deadline: Option<BlockNumber>,
+}
+
+/// Options given when a message is pushed in the queue
+#[derive(Default, Debug, Eq, PartialEq, Clone)]
+pub struct PushOptions {
+    /// type of the message, defined by the application
+    pub type_tag: Option<TypeTag>,
+    /// the message expires after this block
+    pub deadline: Option<BlockNumber>,
+    /// sender saved in the envelope, the caller by default.
+    /// Set the contract address for the messages pushed on behalf of the contract, so the caller can't cancel them.
+    pub sender: Option<Address>,
+}
+
+/// Status of a message not processed yet
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub enum MessageStatus {
+    /// the deadline is passed and the message has been marked as expired
+    Expired,
+    /// the message has been processed before the previous ones
+    Acknowledged,
+    /// a reply has been received for this request (action `ReplyTo`)
+    Answered,
+    /// the message has been cancelled by the sender or an admin
+    Cancelled,
+    /// the replies of the attestors don't agree, the request is closed without reply
+    Failed,
+}
+
+impl MessageStatus {
+    /// Return true if the message is closed (acknowledged, cancelled, expired or failed):
+    /// it is no longer readable and the head of the queue skips it
+    pub fn is_closed(&self) -> bool {
+        matches!(
+            self,
+            MessageStatus::Acknowledged
+                | MessageStatus::Cancelled
+                | MessageStatus::Expired
+                | MessageStatus::Failed
+        )
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct QueuedMessage {
+    /// None for the messages pushed before the envelopes were saved
+    pub envelope: Option<MessageEnvelope>,
+    /// encoded message
+    pub data: Vec<u8>,
+}
+
+/// Limits of the queues, set by the admin
+#[derive(Default, Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct QueueLimits {
+    /// max number of messages not processed yet in each queue (no limit by default)
+    pub max_pending_messages: Option<QueueIndex>,
+    /// max duration of a lease in blocks (`DEFAULT_MAX_LEASE_BLOCKS` by default)
+    pub max_lease_blocks: Option<BlockNumber>,
+}
+
+/// Statistics of a queue, used to monitor the backlog
+#[derive(Default, Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub struct QueueStats {
+    /// number of messages not processed yet
+    pub pending_messages: QueueIndex,
+    /// block when the oldest message not processed yet has been pushed
+    pub oldest_pending_block: Option<BlockNumber>,
+    /// number of messages processed since the creation of the queue (or the last reset of the indexes)
+    pub total_processed: QueueIndex,
+}
+
+/// Claim of a message by an attestor
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct MessageLease {
+    /// attestor processing the message
+    pub attestor: Address,
+    /// the other attestors can claim the message after this block
+    pub until_block: BlockNumber,
+}
+
+/// Messages from `from` (included) to `to` (excluded) claimed by an attestor
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct LeasedRange {
+    pub from: QueueIndex,
+    pub to: QueueIndex,
+    pub lease: MessageLease,
+}
+
+#[derive(Default, Debug)]
+#[ink::storage_item]
+pub struct MessageQueueData {
+    pub messages: Mapping<(QueueId, QueueIndex), QueuedMessage>,
+    pub bounds: Mapping<QueueId, QueueBounds>,
+    pub status: Mapping<(QueueId, QueueIndex), MessageStatus>,
+    /// leases not expired yet (at most `MAX_ACTIVE_LEASES` by queue)
+    pub leases: Mapping<QueueId, Vec<LeasedRange>>,
+    /// pending message pushed with the deduplication key
+    pub dedup: Mapping<(QueueId, DedupKey), QueueIndex>,
+    /// deduplication key of the message, used to clean the table when the message is deleted
+    pub dedup_keys: Mapping<(QueueId, QueueIndex), DedupKey>,
+    pub limits: Lazy<QueueLimits>,
+    /// current epoch of the queues whose indexes have been reset
+    pub epochs: Mapping<(ChannelId, Topic), QueueEpoch>,
+}
+
+impl MessageQueueData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue of the topic in the channel, in its current epoch
+    pub fn get_queue_id(&self, channel: ChannelId, topic: Topic) -> QueueId {
+        QueueId {
+            channel,
+            topic,
+            epoch: self.epochs.get((channel, topic)).unwrap_or_default(),
+        }
+    }
+
+    /// Read the queue as it was saved in the key value store (ie `q/_head`, `q/_tail` and `q/<index>`)
+    /// so the off-chain rollup can still use the keys.
+    /// Return None if the value is not in this storage (ie the queue has not been migrated yet).
+    pub fn get_value(&self, channel: ChannelId, key: &Key) -> Option<Option<Value>> {
+        let suffix = key.strip_prefix(QUEUE_PREFIX)?;
+        let queue = self.get_queue_id(channel, DEFAULT_TOPIC);
+        let bounds = self.bounds.get(queue)?;
+        match suffix {
+            QUEUE_HEAD_KEY => Some(Some(bounds.head.encode())),
+            QUEUE_TAIL_KEY => Some(Some(bounds.tail.encode())),
+            mut id => {
+                let id = QueueIndex::decode_all(&mut id).ok()?;
+                if id < bounds.head || id >= bounds.tail {
+                    // the message has been processed or has not been pushed yet
+                    return Some(None);
+                }
+                if self
+                    .status
+                    .get((queue, id))
+                    .is_some_and(|status| status.is_closed())
+                {
+                    // the message is closed, the workers skip it
+                    return Some(None);
+                }
+                self.messages.get((queue, id)).map(|m| Some(m.data))
+            }
+        }
+    }
+}
+
+pub trait MessageQueueStorage {
+    fn get_storage(&self) -> &MessageQueueData;
+    fn get_mut_storage(&mut self) -> &mut MessageQueueData;
+}
+
 /// Event emitted when a message is push in the queue
 #[ink::event]
 pub struct MessageQueued {
@@ -45,6 +288,16 @@ pub struct MessageQueued {
     data: Vec<u8>,
 }
 
+/// Event emitted with the envelope of each message pushed in a queue
+#[ink::event]
+pub struct MessageEnvelopeSaved {
+    #[ink(topic)]
+    topic: Topic,
+    #[ink(topic)]
+    id: QueueIndex,
+    envelope: MessageEnvelope,
+}
+
 /// Event emitted when a message is processed
 #[ink::event]
 pub struct MessageProcessed {
@@ -52,35 +305,287 @@ pub struct MessageProcessed {
     id: QueueIndex,
 }
 
-pub trait MessageQueue: KvStore {
+/// Event emitted when a message is push in the queue of a topic other than the default one
+#[ink::event]
+pub struct TopicMessageQueued {
+    #[ink(topic)]
+    topic: Topic,
+    #[ink(topic)]
+    id: QueueIndex,
+    data: Vec<u8>,
+}
+
+/// Event emitted when a message is processed in the queue of a topic other than the default one
+#[ink::event]
+pub struct TopicMessageProcessed {
+    #[ink(topic)]
+    topic: Topic,
+    #[ink(topic)]
+    id: QueueIndex,
+}
+
+/// Event emitted when the indexes of an empty queue are reset because the tail reached the max index
+#[ink::event]
+pub struct QueueIndexReset {
+    #[ink(topic)]
+    topic: Topic,
+    epoch: QueueEpoch,
+}
+
+/// Event emitted when a message is acknowledged before the previous ones
+#[ink::event]
+pub struct MessageAcknowledged {
+    #[ink(topic)]
+    id: QueueIndex,
+}
+
+/// Event emitted when a reply is received for a request
+#[ink::event]
+pub struct MessageAnswered {
+    #[ink(topic)]
+    id: QueueIndex,
+}
+
+/// Event emitted when an attestor claims a range of messages
+#[ink::event]
+pub struct MessagesLeased {
+    #[ink(topic)]
+    attestor: Address,
+    from: QueueIndex,
+    to: QueueIndex,
+    until_block: BlockNumber,
+}
+
+/// Event emitted when a message is cancelled
+#[ink::event]
+pub struct MessageCancelled {
+    #[ink(topic)]
+    id: QueueIndex,
+}
+
+/// Event emitted when a message is marked as expired
+#[ink::event]
+pub struct MessageExpired {
+    #[ink(topic)]
+    id: QueueIndex,
+}
+
+/// Event emitted when a request is closed because the replies of the attestors don't agree
+#[ink::event]
+pub struct MessageFailed {
+    #[ink(topic)]
+    id: QueueIndex,
+    error: RollupClientError,
+}
+
+pub trait MessageQueue: KvStore + MessageQueueStorage {
+    /// Topic of the queue used by the methods of this trait
+    fn topic(&self) -> Topic {
+        DEFAULT_TOPIC
+    }
+
+    /// Value read by the off-chain rollup: the keys of the message queue (`q/...`) are read in its own storage
+    fn inner_get_rollup_value(&self, key: &Key) -> Option<Value> {
+        self.inner_get_rollup_channel_value(self.channel_id(), key)
+    }
+
+    fn inner_get_rollup_channel_value(&self, channel: ChannelId, key: &Key) -> Option<Value> {
+        if key.starts_with(QUEUE_PREFIX) {
+            if let Some(value) = MessageQueueStorage::get_storage(self).get_value(channel, key) {
+                return value;
+            }
+        }
+        self.inner_get_channel_value(channel, key)
+    }
+
+    /// Override this method to release the resources kept for a message (a fee, a callback, ...)
+    /// when it is closed without reply (expired, cancelled or failed) and when it is deleted from the queue
+    /// (the messages are deleted in order).
+    /// The resources already released when the message has been answered or closed must be ignored.
+    fn on_message_closed(
+        &mut self,
+        _queue: QueueId,
+        _id: QueueIndex,
+    ) -> Result<(), RollupClientError> {
+        Ok(())
+    }
+
+    /// Queue of the current channel and topic
+    fn queue_id(&self) -> QueueId {
+        self.topic_queue_id(self.topic())
+    }
+
+    /// Queue of the given topic in the current channel
+    fn topic_queue_id(&self, topic: Topic) -> QueueId {
+        MessageQueueStorage::get_storage(self).get_queue_id(self.channel_id(), topic)
+    }
+
+    /// Queue of the default topic in the given channel
+    fn channel_queue_id(&self, channel: ChannelId) -> QueueId {
+        MessageQueueStorage::get_storage(self).get_queue_id(channel, DEFAULT_TOPIC)
+    }
+
     fn push_message<M: ink::scale::Encode>(
         &mut self,
         data: &M,
     ) -> Result<QueueIndex, RollupClientError> {
-        let id = self.get_queue_tail()?;
-        let key = get_key!(id);
-        let encoded_value = data.encode();
-        self.inner_set_value(&key, Some(&encoded_value));
+        self.push_message_with_options(data, PushOptions::default())
+    }
 
-        self.set_queue_tail(
-            id.checked_add(1)
-                .ok_or(RollupClientError::QueueIndexOverflow)?,
-        );
+    /// Push a message with a type defined by the application, saved in the envelope
+    fn push_message_with_tag<M: ink::scale::Encode>(
+        &mut self,
+        data: &M,
+        type_tag: Option<TypeTag>,
+    ) -> Result<QueueIndex, RollupClientError> {
+        let options = PushOptions {
+            type_tag,
+            ..Default::default()
+        };
+        self.push_message_with_options(data, options)
+    }
 
-        ::ink::env::emit_event(MessageQueued {
+    /// Push a message which can't be answered after the given block
+    fn push_message_with_deadline<M: ink::scale::Encode>(
+        &mut self,
+        data: &M,
+        deadline: BlockNumber,
+    ) -> Result<QueueIndex, RollupClientError> {
+        let options = PushOptions {
+            deadline: Some(deadline),
+            ..Default::default()
+        };
+        self.push_message_with_options(data, options)
+    }
+
+    fn push_message_with_options<M: ink::scale::Encode>(
+        &mut self,
+        data: &M,
+        options: PushOptions,
+    ) -> Result<QueueIndex, RollupClientError> {
+        let mut queue = self.queue_id();
+        let mut bounds = self.get_queue_bounds_of(queue)?;
+        if bounds.tail == QueueIndex::MAX {
+            // the queue is saved in a new epoch, so the indexes restart from 0 without conflict with the old messages
+            if bounds.head != bounds.tail {
+                return Err(RollupClientError::QueueIndexOverflow);
+            }
+            queue = self.reset_queue_indexes()?;
+            bounds = QueueBounds::default();
+        }
+        let limits = MessageQueueStorage::get_storage(self)
+            .limits
+            .get()
+            .unwrap_or_default();
+        if limits
+            .max_pending_messages
+            .is_some_and(|max| bounds.tail.saturating_sub(bounds.head) >= max)
+        {
+            return Err(RollupClientError::QueueFull);
+        }
+        let id = bounds.tail;
+        let envelope = MessageEnvelope {
+            sender: options.sender.unwrap_or_else(::ink::env::caller),
+            block_number: ::ink::env::block_number::<::ink::env::DefaultEnvironment>(),
+            timestamp: ::ink::env::block_timestamp::<::ink::env::DefaultEnvironment>(),
+            type_tag: options.type_tag,
+            deadline: options.deadline,
+        };
+        let message = QueuedMessage {
+            envelope: Some(envelope.clone()),
+            data: data.encode(),
+        };
+        MessageQueueStorage::get_mut_storage(self)
+            .messages
+            .insert((queue, id), &message);
+
+        bounds.tail = id
+            .checked_add(1)
+            .ok_or(RollupClientError::QueueIndexOverflow)?;
+        self.set_queue_bounds_of(queue, &bounds);
+
+        match self.topic() {
+            DEFAULT_TOPIC => ::ink::env::emit_event(MessageQueued {
+                id,
+                data: message.data,
+            }),
+            topic => ::ink::env::emit_event(TopicMessageQueued {
+                topic,
+                id,
+                data: message.data,
+            }),
+        }
+        ::ink::env::emit_event(MessageEnvelopeSaved {
+            topic: self.topic(),
             id,
-            data: encoded_value,
+            envelope,
         });
 
         Ok(id)
     }
 
+    /// Start a new epoch for the queue: the indexes restart from 0 and the queue is saved in the new epoch.
+    /// Return the queue in the new epoch.
+    fn reset_queue_indexes(&mut self) -> Result<QueueId, RollupClientError> {
+        let mut queue = self.queue_id();
+        queue.epoch = queue
+            .epoch
+            .checked_add(1)
+            .ok_or(RollupClientError::QueueIndexOverflow)?;
+        MessageQueueStorage::get_mut_storage(self)
+            .epochs
+            .insert((queue.channel, queue.topic), &queue.epoch);
+
+        ::ink::env::emit_event(QueueIndexReset {
+            topic: queue.topic,
+            epoch: queue.epoch,
+        });
+
+        Ok(queue)
+    }
+
+    /// Push a message only if no pending and unexpired message has been pushed with the same key.
+    /// Return the index of the existing message or of the new one.
+    fn push_message_dedup<M: ink::scale::Encode>(
+        &mut self,
+        dedup_key: &DedupKey,
+        data: &M,
+    ) -> Result<QueueIndex, RollupClientError> {
+        let queue = self.queue_id();
+        let existing = MessageQueueStorage::get_storage(self)
+            .dedup
+            .get((queue, dedup_key));
+        if let Some(id) = existing {
+            if let Some(message) = self.get_pending_message(queue, id)? {
+                if !self.is_message_expired(&message, id) {
+                    return Ok(id);
+                }
+            }
+        }
+
+        let id = self.push_message(data)?;
+        // the queue is read again in case its indexes have been reset by the push
+        let queue = self.queue_id();
+        let storage = MessageQueueStorage::get_mut_storage(self);
+        storage.dedup.insert((queue, dedup_key), &id);
+        storage.dedup_keys.insert((queue, id), dedup_key);
+        Ok(id)
+    }
+
+    /// Push a message in the queue of the given topic
+    fn push_message_to<M: ink::scale::Encode>(
+        &mut self,
+        topic: Topic,
+        data: &M,
+    ) -> Result<QueueIndex, RollupClientError> {
+        TopicQueue::new(self, topic).push_message(data)
+    }
+
     fn get_message<M: ink::scale::Decode>(
         &self,
         id: QueueIndex,
     ) -> Result<Option<M>, RollupClientError> {
-        let key = get_key!(id);
-        match self.inner_get_value(&key) {
+        match self.get_raw_message(self.queue_id(), id)? {
             Some(v) => {
                 let message =
                     M::decode(&mut v.as_slice()).map_err(|_| RollupClientError::FailedToDecode)?;
@@ -90,59 +595,788 @@ pub trait MessageQueue: KvStore {
         }
     }
 
+    fn get_raw_message(
+        &self,
+        queue: QueueId,
+        id: QueueIndex,
+    ) -> Result<Option<Vec<u8>>, RollupClientError> {
+        Ok(self.get_pending_message(queue, id)?.map(|m| m.data))
+    }
+
+    fn get_message_envelope(
+        &self,
+        id: QueueIndex,
+    ) -> Result<Option<MessageEnvelope>, RollupClientError> {
+        Ok(self
+            .get_pending_message(self.queue_id(), id)?
+            .and_then(|m| m.envelope))
+    }
+
+    /// Message not processed yet
+    fn get_pending_message(
+        &self,
+        queue: QueueId,
+        id: QueueIndex,
+    ) -> Result<Option<QueuedMessage>, RollupClientError> {
+        let bounds = self.get_queue_bounds_of(queue)?;
+        if id < bounds.head || id >= bounds.tail {
+            // the message has been processed (even if it is not deleted yet) or has not been pushed yet
+            return Ok(None);
+        }
+        let storage = MessageQueueStorage::get_storage(self);
+        if storage
+            .status
+            .get((queue, id))
+            .is_some_and(|status| status.is_closed())
+        {
+            // the message has been processed before the previous ones, cancelled or expired
+            return Ok(None);
+        }
+        let message = match storage.messages.get((queue, id)) {
+            Some(message) => Some(message),
+            // the messages pushed before the migration are still in the key value store
+            None if queue.is_legacy() => self
+                .inner_get_channel_value(queue.channel, &get_key!(id))
+                .map(|data| QueuedMessage {
+                    envelope: None,
+                    data,
+                }),
+            None => None,
+        };
+        Ok(message)
+    }
+
+    fn get_message_status(&self, id: QueueIndex) -> Option<MessageStatus> {
+        MessageQueueStorage::get_storage(self)
+            .status
+            .get((self.queue_id(), id))
+    }
+
+    /// Return true if the message has been marked as expired or if its deadline is passed
+    fn is_message_expired(&self, message: &QueuedMessage, id: QueueIndex) -> bool {
+        if self.get_message_status(id) == Some(MessageStatus::Expired) {
+            return true;
+        }
+        let current_block = ::ink::env::block_number::<::ink::env::DefaultEnvironment>();
+        message
+            .envelope
+            .as_ref()
+            .and_then(|envelope| envelope.deadline)
+            .is_some_and(|deadline| current_block > deadline)
+    }
+
+    /// Mark the message as expired if its deadline is passed: the replies are rejected and the workers skip it.
+    /// Return the message.
+    fn expire_message(&mut self, id: QueueIndex) -> Result<Vec<u8>, RollupClientError> {
+        let queue = self.queue_id();
+        let message = self
+            .get_pending_message(queue, id)?
+            .ok_or(RollupClientError::MessageNotPending)?;
+        if self.get_message_status(id).is_some() {
+            return Err(RollupClientError::MessageNotPending);
+        }
+        if !self.is_message_expired(&message, id) {
+            return Err(RollupClientError::DeadlineNotReached);
+        }
+
+        MessageQueueStorage::get_mut_storage(self)
+            .status
+            .insert((queue, id), &MessageStatus::Expired);
+
+        ::ink::env::emit_event(MessageExpired { id });
+
+        let head = self.get_queue_bounds_of(queue)?.head;
+        self.move_closed_head(head)?;
+        Ok(message.data)
+    }
+
+    /// Close the request without reply when its replies can't be handled (ie the replies of the attestors
+    /// don't agree). Return the request.
+    fn fail_message(
+        &mut self,
+        id: QueueIndex,
+        error: RollupClientError,
+    ) -> Result<Vec<u8>, RollupClientError> {
+        let queue = self.queue_id();
+        let request = self.check_answerable(id)?;
+
+        MessageQueueStorage::get_mut_storage(self)
+            .status
+            .insert((queue, id), &MessageStatus::Failed);
+
+        ::ink::env::emit_event(MessageFailed { id, error });
+
+        let head = self.get_queue_bounds_of(queue)?.head;
+        self.move_closed_head(head)?;
+        Ok(request.data)
+    }
+
     fn has_message(&self) -> Result<bool, RollupClientError> {
-        let current_tail_id = self.get_queue_tail()?;
-        let current_head_id = self.get_queue_head()?;
-        Ok(current_tail_id > current_head_id)
+        let bounds = self.get_queue_bounds_of(self.queue_id())?;
+        Ok(bounds.tail > bounds.head)
+    }
+
+    /// Return true if the queue of the given channel (in its current epoch) has messages not processed yet
+    fn has_channel_message(&self, channel: ChannelId) -> Result<bool, RollupClientError> {
+        let bounds = self.get_queue_bounds_of(self.channel_queue_id(channel))?;
+        Ok(bounds.tail > bounds.head)
     }
 
     fn get_queue_tail(&self) -> Result<QueueIndex, RollupClientError> {
-        let key = get_tail_key!();
-        let index = get_queue_index!(self, key);
-        Ok(index)
+        Ok(self.get_queue_bounds_of(self.queue_id())?.tail)
+    }
+
+    fn get_channel_queue_tail(&self, channel: ChannelId) -> Result<QueueIndex, RollupClientError> {
+        Ok(self
+            .get_queue_bounds_of(self.channel_queue_id(channel))?
+            .tail)
     }
 
     fn get_queue_head(&self) -> Result<QueueIndex, RollupClientError> {
-        let key = get_head_key!();
-        let index = get_queue_index!(self, key);
-        Ok(index)
+        Ok(self.get_queue_bounds_of(self.queue_id())?.head)
+    }
+
+    fn get_channel_queue_head(&self, channel: ChannelId) -> Result<QueueIndex, RollupClientError> {
+        Ok(self
+            .get_queue_bounds_of(self.channel_queue_id(channel))?
+            .head)
+    }
+
+    fn get_queue_bounds_of(&self, queue: QueueId) -> Result<QueueBounds, RollupClientError> {
+        match MessageQueueStorage::get_storage(self).bounds.get(queue) {
+            Some(bounds) => Ok(bounds),
+            None if queue.is_legacy() => {
+                // the queue has not been migrated yet, the bounds are still in the key value store
+                let channel = queue.channel;
+                let head_key = get_head_key!();
+                let tail_key = get_tail_key!();
+                let head = get_queue_index!(self, channel, head_key);
+                Ok(QueueBounds {
+                    head,
+                    tail: get_queue_index!(self, channel, tail_key),
+                    pruned_to: head,
+                })
+            }
+            None => Ok(QueueBounds::default()),
+        }
+    }
+
+    fn set_queue_bounds_of(&mut self, queue: QueueId, bounds: &QueueBounds) {
+        MessageQueueStorage::get_mut_storage(self)
+            .bounds
+            .insert(queue, bounds);
     }
 
     fn pop_to(&mut self, target_id: QueueIndex) -> Result<(), RollupClientError> {
-        let current_tail_id = self.get_queue_tail()?;
-        if target_id > current_tail_id {
+        let queue = self.queue_id();
+        let mut bounds = self.get_queue_bounds_of(queue)?;
+        if target_id > bounds.tail {
             return Err(RollupClientError::InvalidPopTarget);
         }
 
-        let current_head_id = self.get_queue_head()?;
-        if target_id < current_head_id {
+        if target_id < bounds.head {
             return Err(RollupClientError::InvalidPopTarget);
         }
 
-        if target_id == current_head_id {
+        if target_id == bounds.head {
             // nothing to do
             return Ok(());
         }
 
-        for id in current_head_id..target_id {
+        // the messages are unreadable as soon as the head is moved, only a few are deleted now
+        bounds.head = target_id;
+        self.prune_messages(queue, &mut bounds, MAX_PRUNED_MESSAGES_ON_POP);
+        self.set_queue_bounds_of(queue, &bounds);
+
+        match self.topic() {
+            DEFAULT_TOPIC => ::ink::env::emit_event(MessageProcessed { id: target_id }),
+            topic => ::ink::env::emit_event(TopicMessageProcessed {
+                topic,
+                id: target_id,
+            }),
+        }
+
+        Ok(())
+    }
+
+    /// Delete at most `max` messages already processed.
+    /// Return the number of processed messages still to delete.
+    fn prune_queue(&mut self, max: QueueIndex) -> Result<QueueIndex, RollupClientError> {
+        let queue = self.queue_id();
+        let mut bounds = self.get_queue_bounds_of(queue)?;
+        if bounds.pruned_to < bounds.head {
+            self.prune_messages(queue, &mut bounds, max);
+            self.set_queue_bounds_of(queue, &bounds);
+        }
+        Ok(bounds.head.saturating_sub(bounds.pruned_to))
+    }
+
+    fn prune_messages(&mut self, queue: QueueId, bounds: &mut QueueBounds, max: QueueIndex) {
+        let end = bounds.pruned_to.saturating_add(max).min(bounds.head);
+        for id in bounds.pruned_to..end {
+            let storage = MessageQueueStorage::get_mut_storage(self);
+            storage.messages.remove((queue, id));
+            storage.status.remove((queue, id));
+            if let Some(dedup_key) = storage.dedup_keys.take((queue, id)) {
+                if storage.dedup.get((queue, &dedup_key)) == Some(id) {
+                    storage.dedup.remove((queue, dedup_key));
+                }
+            }
+            // release the resources left for the message (skipped by the action `SetQueueHead`, acknowledged, ...).
+            // The hook is called for all the messages deleted, in order, even if they have already been closed
+            // (expired, cancelled or failed).
+            // An error must not block the queue, the resources are kept in this case.
+            let _ = self.on_message_closed(queue, id);
+        }
+        bounds.pruned_to = end;
+    }
+
+    /// Claim the messages from `from` (included) to `to` (excluded) until the given block
+    /// (at most `QueueLimits::max_lease_blocks` from now).
+    /// The messages already leased by another attestor can be claimed only when their lease is expired.
+    fn lease_messages(
+        &mut self,
+        attestor: Address,
+        from: QueueIndex,
+        to: QueueIndex,
+        until_block: BlockNumber,
+    ) -> Result<(), RollupClientError> {
+        let current_block = ::ink::env::block_number::<::ink::env::DefaultEnvironment>();
+        let max_lease_blocks = MessageQueueStorage::get_storage(self)
+            .limits
+            .get()
+            .unwrap_or_default()
+            .max_lease_blocks
+            .unwrap_or(DEFAULT_MAX_LEASE_BLOCKS);
+        if from >= to
+            || to - from > MAX_LEASED_MESSAGES
+            || until_block < current_block
+            || until_block > current_block.saturating_add(max_lease_blocks)
+        {
+            return Err(RollupClientError::InvalidLeaseRange);
+        }
+
+        let queue = self.queue_id();
+        for id in from..to {
+            if self.get_pending_message(queue, id)?.is_none() {
+                return Err(RollupClientError::MessageNotPending);
+            }
+        }
+        self.check_leases(attestor, from, to)?;
+
+        let head = self.get_queue_bounds_of(queue)?.head;
+        let storage = MessageQueueStorage::get_mut_storage(self);
+        let mut leases = storage.leases.get(queue).unwrap_or_default();
+        // forget the expired leases and the ones of the processed messages
+        leases.retain(|range| range.lease.until_block >= current_block && range.to > head);
+        if leases.len() >= MAX_ACTIVE_LEASES as usize {
+            return Err(RollupClientError::TooManyLeases);
+        }
+        leases.push(LeasedRange {
+            from,
+            to,
+            lease: MessageLease {
+                attestor,
+                until_block,
+            },
+        });
+        storage.leases.insert(queue, &leases);
+
+        ::ink::env::emit_event(MessagesLeased {
+            attestor,
+            from,
+            to,
+            until_block,
+        });
+        Ok(())
+    }
+
+    /// Last lease of the message
+    fn get_message_lease(&self, id: QueueIndex) -> Option<MessageLease> {
+        MessageQueueStorage::get_storage(self)
+            .leases
+            .get(self.queue_id())
+            .unwrap_or_default()
+            .into_iter()
+            .rev()
+            .find(|range| range.from <= id && id < range.to)
+            .map(|range| range.lease)
+    }
+
+    /// Return an error if the message is leased by another attestor and the lease is not expired
+    fn check_message_lease(
+        &self,
+        attestor: Address,
+        id: QueueIndex,
+    ) -> Result<(), RollupClientError> {
+        self.check_leases(attestor, id, id.saturating_add(1))
+    }
+
+    /// Return an error if a message from `from` (included) to `to` (excluded) is leased by another attestor
+    /// and the lease is not expired
+    fn check_leases(
+        &self,
+        attestor: Address,
+        from: QueueIndex,
+        to: QueueIndex,
+    ) -> Result<(), RollupClientError> {
+        let current_block = ::ink::env::block_number::<::ink::env::DefaultEnvironment>();
+        let leased = MessageQueueStorage::get_storage(self)
+            .leases
+            .get(self.queue_id())
+            .unwrap_or_default()
+            .iter()
+            .any(|range| {
+                range.lease.attestor != attestor
+                    && range.lease.until_block >= current_block
+                    && range.from < to
+                    && from < range.to
+            });
+        if leased {
+            return Err(RollupClientError::MessageLeased);
+        }
+        Ok(())
+    }
+
+    /// Return an error if a message skipped by moving the head to `target_id` is leased by another attestor
+    fn check_pop_leases(
+        &self,
+        attestor: Address,
+        target_id: QueueIndex,
+    ) -> Result<(), RollupClientError> {
+        let head = self.get_queue_bounds_of(self.queue_id())?.head;
+        self.check_leases(attestor, head, target_id)
+    }
+
+    /// Return the request if it is pending, not expired and not answered yet
+    fn check_answerable(&self, id: QueueIndex) -> Result<QueuedMessage, RollupClientError> {
+        let request = self
+            .get_pending_message(self.queue_id(), id)?
+            .ok_or(RollupClientError::MessageNotPending)?;
+        if self.is_message_expired(&request, id) {
+            return Err(RollupClientError::MessageExpired);
+        }
+        if self.get_message_status(id) == Some(MessageStatus::Answered) {
+            return Err(RollupClientError::MessageAlreadyAnswered);
+        }
+        Ok(request)
+    }
+
+    /// Mark the request as answered. Only one reply is accepted by request.
+    fn answer_message(&mut self, id: QueueIndex) -> Result<QueuedMessage, RollupClientError> {
+        let queue = self.queue_id();
+        let request = self.check_answerable(id)?;
+        MessageQueueStorage::get_mut_storage(self)
+            .status
+            .insert((queue, id), &MessageStatus::Answered);
+        ::ink::env::emit_event(MessageAnswered { id });
+        Ok(request)
+    }
+
+    /// Acknowledge a message processed before the previous ones.
+    /// The head is moved after the acknowledged messages following the head.
+    fn ack_message(&mut self, id: QueueIndex) -> Result<(), RollupClientError> {
+        let queue = self.queue_id();
+        if self.get_pending_message(queue, id)?.is_none() {
+            return Err(RollupClientError::MessageNotPending);
+        }
+
+        let bounds = self.get_queue_bounds_of(queue)?;
+        if id > bounds.head {
+            MessageQueueStorage::get_mut_storage(self)
+                .status
+                .insert((queue, id), &MessageStatus::Acknowledged);
+            ::ink::env::emit_event(MessageAcknowledged { id });
+            return self.move_closed_head(bounds.head);
+        }
+
+        // the head is acknowledged
+        self.move_head_after(id)
+    }
+
+    /// Cancel a pending message: the replies are rejected and the workers skip it. Return the message.
+    fn cancel_message(&mut self, id: QueueIndex) -> Result<Vec<u8>, RollupClientError> {
+        let queue = self.queue_id();
+        let message = self
+            .get_pending_message(queue, id)?
+            .ok_or(RollupClientError::MessageNotPending)?;
+        if self.get_message_status(id).is_some() {
+            return Err(RollupClientError::MessageNotPending);
+        }
+
+        MessageQueueStorage::get_mut_storage(self)
+            .status
+            .insert((queue, id), &MessageStatus::Cancelled);
+        ::ink::env::emit_event(MessageCancelled { id });
+
+        let head = self.get_queue_bounds_of(queue)?.head;
+        self.move_closed_head(head)?;
+        Ok(message.data)
+    }
+
+    /// Move the head after the given message and the following messages already closed
+    /// (at most `MAX_SKIPPED_MESSAGES_ON_POP`, the next ones are skipped by the next acknowledgement)
+    fn move_head_after(&mut self, id: QueueIndex) -> Result<(), RollupClientError> {
+        let bounds = self.get_queue_bounds_of(self.queue_id())?;
+        let mut head = id.saturating_add(1);
+        let end = head
+            .saturating_add(MAX_SKIPPED_MESSAGES_ON_POP)
+            .min(bounds.tail);
+        while head < end && self.is_message_closed(head) {
+            head = head.saturating_add(1);
+        }
+        self.pop_to(head)
+    }
+
+    /// Move the head if it has been left on a closed message
+    fn move_closed_head(&mut self, head: QueueIndex) -> Result<(), RollupClientError> {
+        if self.is_message_closed(head) {
+            return self.move_head_after(head);
+        }
+        Ok(())
+    }
+
+    /// Return true if the message has been acknowledged, cancelled, expired or failed
+    fn is_message_closed(&self, id: QueueIndex) -> bool {
+        self.get_message_status(id)
+            .is_some_and(|status| status.is_closed())
+    }
+
+    fn set_queue_tail(&mut self, id: QueueIndex) -> Result<(), RollupClientError> {
+        let queue = self.queue_id();
+        let mut bounds = self.get_queue_bounds_of(queue)?;
+        bounds.tail = id;
+        self.set_queue_bounds_of(queue, &bounds);
+        Ok(())
+    }
+
+    fn set_queue_head(&mut self, id: QueueIndex) -> Result<(), RollupClientError> {
+        let queue = self.queue_id();
+        let mut bounds = self.get_queue_bounds_of(queue)?;
+        bounds.head = id;
+        self.set_queue_bounds_of(queue, &bounds);
+        Ok(())
+    }
+
+    /// Move at most `max` messages saved in the key value store (previous layout) to the dedicated storage.
+    /// Return the number of messages still to migrate.
+    fn migrate_kv_queue(&mut self, max: QueueIndex) -> Result<QueueIndex, RollupClientError> {
+        let channel = self.channel_id();
+        let head_key = get_head_key!();
+        let tail_key = get_tail_key!();
+        if self.inner_get_channel_value(channel, &tail_key).is_none() {
+            // nothing to migrate
+            return Ok(0);
+        }
+
+        // the messages are moved to the queue of the current epoch.
+        // the bounds are saved first so the messages pushed meanwhile can't override the old ones
+        let queue = self.channel_queue_id(channel);
+        let bounds = self.get_queue_bounds_of(queue)?;
+        self.set_queue_bounds_of(queue, &bounds);
+
+        let legacy_head = get_queue_index!(self, channel, head_key);
+        let legacy_tail = get_queue_index!(self, channel, tail_key);
+        let end = legacy_head.saturating_add(max).min(legacy_tail);
+
+        for id in legacy_head..end {
             let key = get_key!(id);
-            self.inner_set_value(&key, None);
+            let message = self.inner_get_channel_value(channel, &key);
+            self.inner_set_value(&key, None)?;
+            if let Some(message) = message {
+                // the messages already processed are not migrated
+                if id >= bounds.head {
+                    let message = QueuedMessage {
+                        envelope: None,
+                        data: message,
+                    };
+                    MessageQueueStorage::get_mut_storage(self)
+                        .messages
+                        .insert((queue, id), &message);
+                }
+            }
         }
 
-        self.set_queue_head(target_id);
+        if end == legacy_tail {
+            self.inner_set_value(&head_key, None)?;
+            self.inner_set_value(&tail_key, None)?;
+        } else {
+            self.inner_set_value(&head_key, Some(&end.encode()))?;
+        }
 
-        ::ink::env::emit_event(MessageProcessed { id: target_id });
+        Ok(legacy_tail.saturating_sub(end))
+    }
+}
+
+#[ink::trait_definition]
+pub trait MessageQueueQuota {
+    #[ink(message)]
+    fn queue_stats(&self) -> Result<QueueStats, RollupClientError>;
+
+    #[ink(message)]
+    fn get_queue_limits(&self) -> QueueLimits;
+
+    #[ink(message)]
+    fn set_queue_limits(&mut self, limits: QueueLimits) -> Result<(), RollupClientError>;
+}
 
+pub trait BaseMessageQueueQuota: MessageQueue + BaseAccessControl {
+    fn inner_queue_stats(&self) -> Result<QueueStats, RollupClientError> {
+        let queue = self.queue_id();
+        let bounds = self.get_queue_bounds_of(queue)?;
+        let oldest_pending_block = self
+            .get_pending_message(queue, bounds.head)?
+            .and_then(|message| message.envelope)
+            .map(|envelope| envelope.block_number);
+        Ok(QueueStats {
+            pending_messages: bounds.tail.saturating_sub(bounds.head),
+            oldest_pending_block,
+            total_processed: bounds.head,
+        })
+    }
+
+    fn inner_get_queue_limits(&self) -> QueueLimits {
+        MessageQueueStorage::get_storage(self)
+            .limits
+            .get()
+            .unwrap_or_default()
+    }
+
+    fn inner_set_queue_limits(&mut self, limits: QueueLimits) -> Result<(), RollupClientError> {
+        only_role!(self, ADMIN_ROLE);
+        MessageQueueStorage::get_mut_storage(self)
+            .limits
+            .set(&limits);
         Ok(())
     }
+}
+
+/// Message queue restricted to one topic of the current channel: the topic has its own head and tail.
+pub struct TopicQueue<'a, T: ?Sized> {
+    contract: &'a mut T,
+    topic: Topic,
+}
+
+impl<'a, T: ?Sized> TopicQueue<'a, T> {
+    pub fn new(contract: &'a mut T, topic: Topic) -> Self {
+        Self { contract, topic }
+    }
+}
+
+impl<T: KvStoreStorage + ?Sized> KvStoreStorage for TopicQueue<'_, T> {
+    fn get_storage(&self) -> &KvStoreData {
+        KvStoreStorage::get_storage(&*self.contract)
+    }
+
+    fn get_mut_storage(&mut self) -> &mut KvStoreData {
+        KvStoreStorage::get_mut_storage(&mut *self.contract)
+    }
+}
+
+impl<T: KvStore + ?Sized> KvStore for TopicQueue<'_, T> {
+    fn channel_id(&self) -> ChannelId {
+        self.contract.channel_id()
+    }
+
+    fn reserved_prefixes(&self) -> &[&[u8]] {
+        self.contract.reserved_prefixes()
+    }
+}
+
+impl<T: MessageQueueStorage + ?Sized> MessageQueueStorage for TopicQueue<'_, T> {
+    fn get_storage(&self) -> &MessageQueueData {
+        MessageQueueStorage::get_storage(&*self.contract)
+    }
+
+    fn get_mut_storage(&mut self) -> &mut MessageQueueData {
+        MessageQueueStorage::get_mut_storage(&mut *self.contract)
+    }
+}
+
+impl<T: MessageQueue + ?Sized> MessageQueue for TopicQueue<'_, T> {
+    fn topic(&self) -> Topic {
+        self.topic
+    }
+
+    fn on_message_closed(
+        &mut self,
+        queue: QueueId,
+        id: QueueIndex,
+    ) -> Result<(), RollupClientError> {
+        self.contract.on_message_closed(queue, id)
+    }
+}
+
+impl<T: AccessControlStorage + ?Sized> AccessControlStorage for TopicQueue<'_, T> {
+    fn get_storage(&self) -> &AccessControlData {
+        AccessControlStorage::get_storage(&*self.contract)
+    }
+
+    fn get_mut_storage(&mut self) -> &mut AccessControlData {
+        AccessControlStorage::get_mut_storage(&mut *self.contract)
+    }
+}
+
+/// The topics share the roles of their channel
+impl<T: BaseAccessControl + ?Sized> BaseAccessControl for TopicQueue<'_, T> {
+    fn inner_has_role(&self, role: RoleType, account: Address) -> bool {
+        self.contract.inner_has_role(role, account)
+    }
+
+    fn inner_add_role(&mut self, role: RoleType, account: Address) {
+        self.contract.inner_add_role(role, account)
+    }
+
+    fn inner_remove_role(&mut self, role: RoleType, account: Address) {
+        self.contract.inner_remove_role(role, account)
+    }
+}
+
+/// The actions on the messages of a topic (reply, acknowledgement, lease, ...) are handled by the hooks
+/// of the contract, the hooks receiving a `QueueId` can tell the topic of the message
+impl<T: BaseRollupClient + ?Sized> BaseRollupClient for TopicQueue<'_, T> {
+    fn keep_key_metadata(&self) -> bool {
+        self.contract.keep_key_metadata()
+    }
+
+    fn collect_reply(
+        &mut self,
+        queue: QueueId,
+        attestor: Address,
+        id: QueueIndex,
+        payload: Vec<u8>,
+    ) -> Result<Option<Vec<CandidateReply>>, RollupClientError> {
+        self.contract.collect_reply(queue, attestor, id, payload)
+    }
+
+    fn take_timed_out_replies(
+        &mut self,
+        queue: QueueId,
+        id: QueueIndex,
+    ) -> Result<Vec<CandidateReply>, RollupClientError> {
+        self.contract.take_timed_out_replies(queue, id)
+    }
+
+    fn on_message_answered(
+        &mut self,
+        queue: QueueId,
+        id: QueueIndex,
+        attestors: Vec<Address>,
+    ) -> Result<(), RollupClientError> {
+        self.contract.on_message_answered(queue, id, attestors)
+    }
+
+    fn on_messages_consumed(
+        &mut self,
+        queue: QueueId,
+        attestor: Address,
+        from: QueueIndex,
+        to: QueueIndex,
+    ) -> Result<(), RollupClientError> {
+        self.contract
+            .on_messages_consumed(queue, attestor, from, to)
+    }
+
+    fn on_reply_rejected(
+        &mut self,
+        channel: ChannelId,
+        letter: DeadLetter,
+    ) -> Result<(), RollupClientError> {
+        self.contract.on_reply_rejected(channel, letter)
+    }
 
-    fn set_queue_tail(&mut self, id: QueueIndex) {
-        let key = get_tail_key!();
-        self.inner_set_value(&key, Some(&id.encode()));
+    fn callback_requester(&mut self, queue: QueueId, id: QueueIndex, response: &[u8]) -> bool {
+        self.contract.callback_requester(queue, id, response)
     }
 
-    fn set_queue_head(&mut self, id: QueueIndex) {
-        let key = get_head_key!();
-        self.inner_set_value(&key, Some(&id.encode()));
+    fn on_rollup_applied(&mut self, channel: ChannelId) -> Result<(), RollupClientError> {
+        self.contract.on_rollup_applied(channel)
+    }
+
+    fn attestor_role(&self) -> RoleType {
+        self.contract.attestor_role()
+    }
+
+    fn on_message_received(&mut self, action: Vec<u8>) -> Result<(), RollupClientError> {
+        self.contract.on_message_received(action)
+    }
+
+    fn on_reply(&mut self, request: Vec<u8>, response: Vec<u8>) -> Result<(), RollupClientError> {
+        self.contract.on_reply(request, response)
+    }
+
+    fn aggregate(
+        &mut self,
+        channel: ChannelId,
+        replies: Vec<CandidateReply>,
+    ) -> Result<Vec<u8>, RollupClientError> {
+        self.contract.aggregate(channel, replies)
+    }
+
+    fn on_message_expired(
+        &mut self,
+        channel: ChannelId,
+        id: QueueIndex,
+        message: Vec<u8>,
+    ) -> Result<(), RollupClientError> {
+        self.contract.on_message_expired(channel, id, message)
+    }
+
+    fn on_message_cancelled(
+        &mut self,
+        channel: ChannelId,
+        id: QueueIndex,
+        message: Vec<u8>,
+    ) -> Result<(), RollupClientError> {
+        self.contract.on_message_cancelled(channel, id, message)
+    }
+
+    fn on_message_failed(
+        &mut self,
+        channel: ChannelId,
+        id: QueueIndex,
+        message: Vec<u8>,
+    ) -> Result<(), RollupClientError> {
+        self.contract.on_message_failed(channel, id, message)
+    }
+
+    fn on_channel_message_received(
+        &mut self,
+        channel: ChannelId,
+        action: Vec<u8>,
+    ) -> Result<(), RollupClientError> {
+        self.contract.on_channel_message_received(channel, action)
+    }
+
+    fn on_channel_reply(
+        &mut self,
+        channel: ChannelId,
+        request: Vec<u8>,
+        response: Vec<u8>,
+    ) -> Result<(), RollupClientError> {
+        self.contract.on_channel_reply(channel, request, response)
+    }
+}
+
+#[ink::trait_definition]
+pub trait MessageQueueMigration {
+    /// Move at most `max` messages of the channel from the key value store to the dedicated storage.
+    /// Return the number of messages still to migrate.
+    #[ink(message)]
+    fn migrate_message_queue(
+        &mut self,
+        channel: ChannelId,
+        max: QueueIndex,
+    ) -> Result<QueueIndex, RollupClientError>;
+}
+
+pub trait BaseMessageQueueMigration: MessageQueue + BaseAccessControl {
+    fn inner_migrate_message_queue(
+        &mut self,
+        channel: ChannelId,
+        max: QueueIndex,
+    ) -> Result<QueueIndex, RollupClientError> {
+        only_role!(self, ADMIN_ROLE);
+        RollupChannel::new(self, channel).migrate_kv_queue(max)
     }
 }
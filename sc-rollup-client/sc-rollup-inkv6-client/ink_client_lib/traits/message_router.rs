@@ -0,0 +1,88 @@
+use crate::traits::RollupClientError;
+use ink::prelude::vec::Vec;
+use ink::scale::{Decode, DecodeAll, Encode};
+
+pub use inkv6_client_macros::TypedMessage;
+
+/// Id of a kind of message. Use `#[derive(TypedMessage)]` to derive it from the name of the type.
+pub type MessageTypeId = u32;
+/// Version of the schema of a kind of message
+pub type SchemaVersion = u16;
+
+/// Message exchanged with the worker, tagged with its type and the version of its schema
+pub trait TypedMessage: Encode + Decode {
+    const TYPE_ID: MessageTypeId;
+    const VERSION: SchemaVersion;
+}
+
+/// Encoded message with its type and the version of its schema
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub struct TypedEnvelope {
+    pub type_id: MessageTypeId,
+    pub version: SchemaVersion,
+    pub payload: Vec<u8>,
+}
+
+impl TypedEnvelope {
+    pub fn new<M: TypedMessage>(message: &M) -> Self {
+        Self {
+            type_id: M::TYPE_ID,
+            version: M::VERSION,
+            payload: message.encode(),
+        }
+    }
+
+    pub fn is<M: TypedMessage>(&self) -> bool {
+        self.type_id == M::TYPE_ID && self.version == M::VERSION
+    }
+}
+
+/// Dispatch a `TypedEnvelope` to the handler registered for its type and version.
+///
+/// ```ignore
+/// fn on_message_received(&mut self, action: Vec<u8>) -> Result<(), RollupClientError> {
+///     MessageRouter::new(self, &action)?
+///         .route(Self::on_price_v1)
+///         .route(Self::on_price)
+///         .dispatch()
+/// }
+/// ```
+pub struct MessageRouter<'a, C: ?Sized> {
+    contract: &'a mut C,
+    envelope: TypedEnvelope,
+    result: Option<Result<(), RollupClientError>>,
+}
+
+impl<'a, C: ?Sized> MessageRouter<'a, C> {
+    pub fn new(contract: &'a mut C, data: &[u8]) -> Result<Self, RollupClientError> {
+        let envelope = TypedEnvelope::decode_all(&mut &data[..])
+            .map_err(|_| RollupClientError::FailedToDecode)?;
+        Ok(Self {
+            contract,
+            envelope,
+            result: None,
+        })
+    }
+
+    /// Call the handler if the message has the type and the version of `M`
+    pub fn route<M: TypedMessage>(
+        mut self,
+        handler: fn(&mut C, M) -> Result<(), RollupClientError>,
+    ) -> Self {
+        if self.result.is_none() && self.envelope.is::<M>() {
+            self.result = Some(
+                M::decode_all(&mut self.envelope.payload.as_slice())
+                    .map_err(|_| RollupClientError::FailedToDecode)
+                    .and_then(|message| handler(self.contract, message)),
+            );
+        }
+        self
+    }
+
+    /// Result of the handler, `UnsupportedAction` if no handler is registered for the type and the version
+    pub fn dispatch(self) -> Result<(), RollupClientError> {
+        self.result
+            .unwrap_or(Err(RollupClientError::UnsupportedAction))
+    }
+}
@@ -1,5 +1,6 @@
+use crate::traits::channel::{ChannelId, RollupChannel};
 use crate::traits::kv_store::{Key, Value};
-use crate::traits::rollup_client::{BaseRollupClient, HandleActionInput};
+use crate::traits::rollup_client::{BaseRollupClient, HandleActionInput, RollupCondEqMethodParams};
 use crate::traits::RollupClientError;
 use ink::env::hash::{Blake2x256, HashOutput};
 use ink::prelude::vec::Vec;
@@ -10,7 +11,9 @@ use ink::Address;
 
 pub type Nonce = u128;
 
-type RollupCondEqMethodParams = (
+/// Data signed for a meta transaction in a channel: the channel is signed with the rollup
+pub type ChannelRollupCondEqMethodParams = (
+    ChannelId,
     Vec<(Key, Option<Value>)>,
     Vec<(Key, Option<Value>)>,
     Vec<HandleActionInput>,
@@ -61,6 +64,13 @@ pub trait MetaTransaction {
         request: ForwardRequest,
         signature: [u8; 65],
     ) -> Result<(), RollupClientError>;
+
+    #[ink(message)]
+    fn meta_tx_channel_rollup_cond_eq(
+        &mut self,
+        request: ForwardRequest,
+        signature: [u8; 65],
+    ) -> Result<(), RollupClientError>;
 }
 
 pub trait BaseMetaTransaction: MetaTransactionStorage + BaseRollupClient {
@@ -159,6 +169,35 @@ pub trait BaseMetaTransaction: MetaTransactionStorage + BaseRollupClient {
 
         Ok(())
     }
+
+    /// Rollup in the channel given in the data (`ChannelRollupCondEqMethodParams`),
+    /// the signer must have the attestor role in this channel
+    fn inner_meta_tx_channel_rollup_cond_eq(
+        &mut self,
+        request: ForwardRequest,
+        signature: [u8; 65],
+    ) -> Result<(), RollupClientError> {
+        // check the signature
+        self.ensure_meta_tx_valid(&request, &signature)?;
+
+        // decode the data
+        let (channel, conditions, updates, actions): ChannelRollupCondEqMethodParams =
+            scale::Decode::decode(&mut request.data.as_slice())
+                .map_err(|_| RollupClientError::FailedToDecode)?;
+
+        // emit the event
+        ::ink::env::emit_event(MetaTransactionDecoded {});
+
+        // call the rollup with the attestor role of the channel
+        RollupChannel::new(self, channel).inner_rollup_cond_eq_with_attestor(
+            request.from,
+            conditions,
+            updates,
+            actions,
+        )?;
+
+        Ok(())
+    }
 }
 
 /// Hashing function for bytes
@@ -1,17 +1,27 @@
 use crate::traits::access_control::AccessControlError;
 
 pub mod access_control;
+pub mod channel;
+pub mod dead_letter_queue;
+pub mod encrypted_payload;
+pub mod fee_escrow;
 pub mod kv_store;
 pub mod message_queue;
+pub mod message_router;
 pub mod meta_transaction;
+pub mod reply_quorum;
+pub mod request_hub;
 pub mod rollup_client;
+pub mod scheduled_request;
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone)]
 #[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
 #[allow(clippy::cast_possible_truncation)]
 pub enum RollupClientError {
     InvalidPopTarget,
-    ConditionNotMet,
+    /// index of the first condition not met
+    ConditionNotMet(u32),
     UnsupportedAction,
     FailedToDecode,
     QueueIndexOverflow,
@@ -25,6 +35,27 @@ pub enum RollupClientError {
     NonceOverflow,
     RuntimeError(u128),
     BusinessError(u128),
+    ReservedKey,
+    KeyTooLarge,
+    ValueTooLarge,
+    StorageQuotaExceeded,
+    MessageNotPending,
+    DeadlineNotReached,
+    MessageExpired,
+    DeadLetterNotFound,
+    MessageAlreadyAnswered,
+    InvalidLeaseRange,
+    MessageLeased,
+    QueueFull,
+    InsufficientFee,
+    TransferFailed,
+    UnknownEncryptionKey,
+    InvalidSchedule,
+    TooManySchedules,
+    ScheduleNotFound,
+    DuplicateReply,
+    QuorumNotReached,
+    TooManyLeases,
 }
 
 impl From<AccessControlError> for RollupClientError {
@@ -0,0 +1,195 @@
+use crate::only_role;
+use crate::traits::access_control::{BaseAccessControl, ADMIN_ROLE};
+use crate::traits::kv_store::BlockNumber;
+use crate::traits::message_queue::{QueueId, QueueIndex};
+use crate::traits::rollup_client::BaseRollupClient;
+use crate::traits::RollupClientError;
+use ink::prelude::vec::Vec;
+use ink::storage::{Lazy, Mapping};
+use ink::Address;
+
+/// Reply sent by an attestor
+pub type CandidateReply = (Address, Vec<u8>);
+
+/// Number of replies from distinct attestors needed before the reply is handled
+#[derive(Default, Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct QuorumConfig {
+    /// the first reply is handled if the quorum is 0 or 1
+    pub quorum: u32,
+    /// after this number of blocks from the first reply, the replies received can be handled without the quorum
+    /// (0: no timeout, the quorum must be reached)
+    pub timeout: BlockNumber,
+}
+
+/// Replies received for a request while the quorum is not reached
+#[derive(Default, Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct PendingReplies {
+    /// block of the first reply
+    pub since: BlockNumber,
+    pub replies: Vec<CandidateReply>,
+}
+
+#[derive(Default, Debug)]
+#[ink::storage_item]
+pub struct ReplyQuorumData {
+    pub config: Lazy<QuorumConfig>,
+    pub pending: Mapping<(QueueId, QueueIndex), PendingReplies>,
+}
+
+impl ReplyQuorumData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+pub trait ReplyQuorumStorage {
+    fn get_storage(&self) -> &ReplyQuorumData;
+    fn get_mut_storage(&mut self) -> &mut ReplyQuorumData;
+}
+
+/// Event emitted when a reply is saved until the quorum is reached
+#[ink::event]
+pub struct ReplyCollected {
+    #[ink(topic)]
+    id: QueueIndex,
+    #[ink(topic)]
+    attestor: Address,
+    nb_replies: u32,
+}
+
+/// Reply sent by more than half of the quorum (None if there is no such reply)
+pub fn majority_reply(replies: &[CandidateReply], quorum: u32) -> Option<Vec<u8>> {
+    let quorum = usize::try_from(quorum).unwrap_or(usize::MAX);
+    replies
+        .iter()
+        .map(|(_, payload)| payload)
+        .find(|payload| {
+            let count = replies.iter().filter(|(_, p)| p == *payload).count();
+            count.saturating_mul(2) > quorum
+        })
+        .cloned()
+}
+
+#[ink::trait_definition]
+pub trait ReplyQuorum {
+    #[ink(message)]
+    fn get_quorum_config(&self) -> QuorumConfig;
+
+    #[ink(message)]
+    fn set_quorum_config(&mut self, config: QuorumConfig) -> Result<(), RollupClientError>;
+
+    #[ink(message)]
+    fn get_pending_replies(&self, id: QueueIndex) -> Option<PendingReplies>;
+}
+
+pub trait BaseReplyQuorum: BaseRollupClient + ReplyQuorumStorage {
+    /// Collect the reply of the attestor. Call this method in the hook `collect_reply` to use the quorum.
+    /// Return the replies to aggregate once the quorum is reached (the reply itself if there is no quorum).
+    fn inner_collect_reply(
+        &mut self,
+        queue: QueueId,
+        attestor: Address,
+        id: QueueIndex,
+        payload: Vec<u8>,
+    ) -> Result<Option<Vec<CandidateReply>>, RollupClientError> {
+        let config = self.inner_get_quorum_config();
+        if config.quorum <= 1 {
+            return Ok(Some(ink::prelude::vec![(attestor, payload)]));
+        }
+
+        let storage = ReplyQuorumStorage::get_mut_storage(self);
+        let mut pending = storage
+            .pending
+            .get((queue, id))
+            .unwrap_or_else(|| PendingReplies {
+                since: ::ink::env::block_number::<::ink::env::DefaultEnvironment>(),
+                replies: Vec::new(),
+            });
+        if pending
+            .replies
+            .iter()
+            .any(|(address, _)| *address == attestor)
+        {
+            return Err(RollupClientError::DuplicateReply);
+        }
+        pending.replies.push((attestor, payload));
+
+        let nb_replies = u32::try_from(pending.replies.len()).unwrap_or(u32::MAX);
+        if nb_replies >= config.quorum {
+            storage.pending.remove((queue, id));
+            return Ok(Some(pending.replies));
+        }
+        storage.pending.insert((queue, id), &pending);
+        ::ink::env::emit_event(ReplyCollected {
+            id,
+            attestor,
+            nb_replies,
+        });
+        Ok(None)
+    }
+
+    /// Return the replies received for the request if the quorum has not been reached before the timeout.
+    /// Call this method in the hook `take_timed_out_replies` to use the quorum.
+    /// Without timeout (0), the replies are handled only when the quorum is reached.
+    fn inner_take_timed_out_replies(
+        &mut self,
+        queue: QueueId,
+        id: QueueIndex,
+    ) -> Result<Vec<CandidateReply>, RollupClientError> {
+        let timeout = self.inner_get_quorum_config().timeout;
+        if timeout == 0 {
+            return Err(RollupClientError::QuorumNotReached);
+        }
+        let storage = ReplyQuorumStorage::get_mut_storage(self);
+        let pending = storage
+            .pending
+            .get((queue, id))
+            .ok_or(RollupClientError::QuorumNotReached)?;
+        let block_number = ::ink::env::block_number::<::ink::env::DefaultEnvironment>();
+        if block_number < pending.since.saturating_add(timeout) {
+            return Err(RollupClientError::QuorumNotReached);
+        }
+        storage.pending.remove((queue, id));
+        Ok(pending.replies)
+    }
+
+    /// Return the reply sent by more than half of the quorum, even if the replies are handled after the timeout.
+    /// Call this method in the hook `aggregate` to use the quorum.
+    fn inner_aggregate(&self, replies: &[CandidateReply]) -> Result<Vec<u8>, RollupClientError> {
+        let quorum = self.inner_get_quorum_config().quorum.max(1);
+        majority_reply(replies, quorum).ok_or(RollupClientError::QuorumNotReached)
+    }
+
+    /// Remove the replies collected for the request.
+    /// Call this method in the hook `on_message_closed` so the replies of the closed requests are not kept forever.
+    fn remove_pending_replies(&mut self, queue: QueueId, id: QueueIndex) -> Option<PendingReplies> {
+        ReplyQuorumStorage::get_mut_storage(self)
+            .pending
+            .take((queue, id))
+    }
+
+    fn inner_get_quorum_config(&self) -> QuorumConfig {
+        ReplyQuorumStorage::get_storage(self)
+            .config
+            .get()
+            .unwrap_or_default()
+    }
+
+    fn inner_set_quorum_config(&mut self, config: QuorumConfig) -> Result<(), RollupClientError> {
+        only_role!(self, ADMIN_ROLE);
+        ReplyQuorumStorage::get_mut_storage(self)
+            .config
+            .set(&config);
+        Ok(())
+    }
+
+    fn inner_get_pending_replies(&self, id: QueueIndex) -> Option<PendingReplies> {
+        ReplyQuorumStorage::get_storage(self)
+            .pending
+            .get((self.queue_id(), id))
+    }
+}
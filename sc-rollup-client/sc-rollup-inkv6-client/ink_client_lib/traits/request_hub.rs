@@ -0,0 +1,207 @@
+use crate::only_role;
+use crate::traits::access_control::{BaseAccessControl, ADMIN_ROLE};
+use crate::traits::message_queue::{PushOptions, QueueId, QueueIndex, TypeTag};
+use crate::traits::rollup_client::BaseRollupClient;
+use crate::traits::RollupClientError;
+use ink::env::call::{build_call, ExecutionInput, Selector};
+use ink::env::DefaultEnvironment;
+use ink::prelude::vec::Vec;
+use ink::storage::{Lazy, Mapping};
+use ink::Address;
+
+/// Type of the messages pushed by the hub (see `MessageEnvelope::type_tag`)
+pub const HUB_REQUEST_TYPE: TypeTag = ink::selector_id!("HubRequest");
+
+/// Selector of the message called on the requester with `(request_id: QueueIndex, response: Vec<u8>)`
+pub type CallbackSelector = [u8; 4];
+
+/// Message pushed in the queue by the hub, the worker knows which contract asks for the data
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub struct HubMessage {
+    pub requester: Address,
+    pub payload: Vec<u8>,
+}
+
+/// Contract to call back when the reply is received
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct HubRequest {
+    pub requester: Address,
+    pub callback_selector: CallbackSelector,
+}
+
+/// Resources given to a callback, so a failing or greedy requester can't block the replies of the others
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct CallbackLimits {
+    pub ref_time: u64,
+    pub proof_size: u64,
+}
+
+impl Default for CallbackLimits {
+    fn default() -> Self {
+        Self {
+            ref_time: 5_000_000_000,
+            proof_size: 256 * 1024,
+        }
+    }
+}
+
+#[derive(Default, Debug)]
+#[ink::storage_item]
+pub struct RequestHubData {
+    pub requests: Mapping<(QueueId, QueueIndex), HubRequest>,
+    pub callback_limits: Lazy<CallbackLimits>,
+}
+
+impl RequestHubData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+pub trait RequestHubStorage {
+    fn get_storage(&self) -> &RequestHubData;
+    fn get_mut_storage(&mut self) -> &mut RequestHubData;
+}
+
+/// Event emitted when another contract sends a request
+#[ink::event]
+pub struct RequestReceived {
+    #[ink(topic)]
+    id: QueueIndex,
+    #[ink(topic)]
+    requester: Address,
+}
+
+/// Event emitted when the requester has been called back with the reply
+#[ink::event]
+pub struct CallbackSucceeded {
+    #[ink(topic)]
+    id: QueueIndex,
+    #[ink(topic)]
+    requester: Address,
+}
+
+/// Event emitted when the callback fails (the reply is dropped, the rest of the batch is applied)
+#[ink::event]
+pub struct CallbackFailed {
+    #[ink(topic)]
+    id: QueueIndex,
+    #[ink(topic)]
+    requester: Address,
+}
+
+#[ink::trait_definition]
+pub trait RequestHub {
+    /// Request called by another contract, the reply is sent to `callback_selector`
+    #[ink(message)]
+    fn request(
+        &mut self,
+        payload: Vec<u8>,
+        callback_selector: CallbackSelector,
+    ) -> Result<QueueIndex, RollupClientError>;
+
+    #[ink(message)]
+    fn get_hub_request(&self, id: QueueIndex) -> Option<HubRequest>;
+
+    #[ink(message)]
+    fn get_callback_limits(&self) -> CallbackLimits;
+
+    #[ink(message)]
+    fn set_callback_limits(&mut self, limits: CallbackLimits) -> Result<(), RollupClientError>;
+}
+
+pub trait BaseRequestHub: BaseRollupClient + RequestHubStorage {
+    fn inner_request(
+        &mut self,
+        payload: Vec<u8>,
+        callback_selector: CallbackSelector,
+    ) -> Result<QueueIndex, RollupClientError> {
+        let requester = ::ink::env::caller();
+        let options = PushOptions {
+            type_tag: Some(HUB_REQUEST_TYPE),
+            ..Default::default()
+        };
+        let id = self.push_message_with_options(&HubMessage { requester, payload }, options)?;
+        let queue = self.queue_id();
+        RequestHubStorage::get_mut_storage(self).requests.insert(
+            (queue, id),
+            &HubRequest {
+                requester,
+                callback_selector,
+            },
+        );
+        ::ink::env::emit_event(RequestReceived { id, requester });
+        Ok(id)
+    }
+
+    /// Remove the request sent by another contract (None if the message has been pushed by the contract itself).
+    /// Call this method in the hook `on_message_closed` so the requests without reply are not kept forever.
+    fn remove_hub_request(&mut self, queue: QueueId, id: QueueIndex) -> Option<HubRequest> {
+        RequestHubStorage::get_mut_storage(self)
+            .requests
+            .take((queue, id))
+    }
+
+    /// Call back the requester with the reply. Return false if the message has not been sent by another contract.
+    /// The call is limited by `CallbackLimits` and its failure doesn't revert the transaction.
+    fn call_requester(&mut self, queue: QueueId, id: QueueIndex, response: &[u8]) -> bool {
+        let Some(request) = self.remove_hub_request(queue, id) else {
+            return false;
+        };
+        let limits = self.inner_get_callback_limits();
+
+        let result = build_call::<DefaultEnvironment>()
+            .call(request.requester)
+            .ref_time_limit(limits.ref_time)
+            .proof_size_limit(limits.proof_size)
+            .exec_input(
+                ExecutionInput::new(Selector::new(request.callback_selector))
+                    .push_arg(id)
+                    .push_arg(response),
+            )
+            .returns::<()>()
+            .try_invoke();
+
+        if matches!(result, Ok(Ok(()))) {
+            ::ink::env::emit_event(CallbackSucceeded {
+                id,
+                requester: request.requester,
+            });
+        } else {
+            ::ink::env::emit_event(CallbackFailed {
+                id,
+                requester: request.requester,
+            });
+        }
+        true
+    }
+
+    fn inner_get_hub_request(&self, id: QueueIndex) -> Option<HubRequest> {
+        RequestHubStorage::get_storage(self)
+            .requests
+            .get((self.queue_id(), id))
+    }
+
+    fn inner_get_callback_limits(&self) -> CallbackLimits {
+        RequestHubStorage::get_storage(self)
+            .callback_limits
+            .get()
+            .unwrap_or_default()
+    }
+
+    fn inner_set_callback_limits(
+        &mut self,
+        limits: CallbackLimits,
+    ) -> Result<(), RollupClientError> {
+        only_role!(self, ADMIN_ROLE);
+        RequestHubStorage::get_mut_storage(self)
+            .callback_limits
+            .set(&limits);
+        Ok(())
+    }
+}
@@ -1,12 +1,22 @@
-use crate::traits::access_control::{BaseAccessControl, RoleType};
-use crate::traits::kv_store::{Key, Value};
-use crate::traits::message_queue::{MessageQueue, QueueIndex};
+use crate::only_role;
+use crate::traits::access_control::{BaseAccessControl, RoleType, ADMIN_ROLE};
+use crate::traits::channel::ChannelId;
+use crate::traits::dead_letter_queue::DeadLetter;
+use crate::traits::kv_store::{BlockNumber, Key, KeyMetadata, KvStoreStorage, Value};
+use crate::traits::message_queue::{
+    MessageEnvelope, MessageLease, MessageQueue, QueueBounds, QueueId, QueueIndex, Topic,
+    TopicQueue,
+};
+use crate::traits::reply_quorum::{majority_reply, CandidateReply};
 use crate::traits::RollupClientError;
 use ink::prelude::vec::Vec;
 use ink::Address;
 
 pub const ATTESTOR_ROLE: RoleType = ink::selector_id!("ATTESTOR_ROLE");
 
+/// Max number of messages returned by `get_messages`
+pub const MAX_MESSAGES_BY_PAGE: QueueIndex = 100;
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 #[ink::scale_derive(Encode, Decode, TypeInfo)]
 #[allow(clippy::cast_possible_truncation)]
@@ -15,6 +25,61 @@ pub enum HandleActionInput {
     SetQueueHead(QueueIndex),
     GrantAttestor(Address),
     RevokeAttestor(Address),
+    ExpireMessages(Vec<QueueIndex>),
+    ReplyTo {
+        request_id: QueueIndex,
+        payload: Vec<u8>,
+    },
+    Ack(Vec<QueueIndex>),
+    SetTopicQueueHead {
+        topic: Topic,
+        id: QueueIndex,
+    },
+    Lease {
+        from: QueueIndex,
+        to: QueueIndex,
+        until_block: BlockNumber,
+    },
+    /// handle the replies received for the request when the quorum is not reached before the timeout
+    FinalizeReplies(QueueIndex),
+    /// same as `ReplyTo` for a request of the topic queue
+    TopicReplyTo {
+        topic: Topic,
+        request_id: QueueIndex,
+        payload: Vec<u8>,
+    },
+    /// same as `Ack` for messages of the topic queue
+    TopicAck {
+        topic: Topic,
+        ids: Vec<QueueIndex>,
+    },
+    /// same as `Lease` for messages of the topic queue
+    TopicLease {
+        topic: Topic,
+        from: QueueIndex,
+        to: QueueIndex,
+        until_block: BlockNumber,
+    },
+    /// same as `ExpireMessages` for messages of the topic queue
+    ExpireTopicMessages {
+        topic: Topic,
+        ids: Vec<QueueIndex>,
+    },
+    /// same as `FinalizeReplies` for a request of the topic queue
+    FinalizeTopicReplies {
+        topic: Topic,
+        request_id: QueueIndex,
+    },
+}
+
+/// Result of a condition checked against the current state of the store
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub struct ConditionResult {
+    /// true if the current value is equal to the expected value
+    pub is_met: bool,
+    /// value currently saved in the store
+    pub current_value: Option<Value>,
 }
 
 pub type RollupCondEqMethodParams = (
@@ -28,9 +93,25 @@ pub trait RollupClient {
     #[ink(message)]
     fn get_value(&self, key: Key) -> Option<Value>;
 
+    #[ink(message)]
+    fn get_value_with_meta(&self, key: Key) -> (Option<Value>, Option<KeyMetadata>);
+
     #[ink(message)]
     fn has_message(&self) -> Result<bool, RollupClientError>;
 
+    #[ink(message)]
+    fn get_queue_bounds(&self) -> Result<QueueBounds, RollupClientError>;
+
+    #[ink(message)]
+    fn get_message(&self, id: QueueIndex) -> Result<Option<Vec<u8>>, RollupClientError>;
+
+    #[ink(message)]
+    fn get_messages(
+        &self,
+        from: QueueIndex,
+        limit: QueueIndex,
+    ) -> Result<Vec<(QueueIndex, Vec<u8>)>, RollupClientError>;
+
     #[ink(message)]
     fn rollup_cond_eq(
         &mut self,
@@ -40,6 +121,71 @@ pub trait RollupClient {
     ) -> Result<(), RollupClientError>;
 }
 
+#[ink::trait_definition]
+pub trait MessageQueueReader {
+    #[ink(message)]
+    fn get_topic_queue_bounds(&self, topic: Topic) -> Result<QueueBounds, RollupClientError>;
+
+    #[ink(message)]
+    fn get_topic_messages(
+        &self,
+        topic: Topic,
+        from: QueueIndex,
+        limit: QueueIndex,
+    ) -> Result<Vec<(QueueIndex, Vec<u8>)>, RollupClientError>;
+
+    #[ink(message)]
+    fn get_message_lease(&self, id: QueueIndex) -> Option<MessageLease>;
+
+    #[ink(message)]
+    fn get_message_envelope(
+        &self,
+        id: QueueIndex,
+    ) -> Result<Option<MessageEnvelope>, RollupClientError>;
+}
+
+#[ink::trait_definition]
+pub trait MessageLifecycle {
+    #[ink(message)]
+    fn prune_queue(&mut self, max: QueueIndex) -> Result<QueueIndex, RollupClientError>;
+
+    #[ink(message)]
+    fn expire(&mut self, id: QueueIndex) -> Result<(), RollupClientError>;
+
+    #[ink(message)]
+    fn cancel(&mut self, id: QueueIndex) -> Result<(), RollupClientError>;
+
+    #[ink(message)]
+    fn expire_topic_message(
+        &mut self,
+        topic: Topic,
+        id: QueueIndex,
+    ) -> Result<(), RollupClientError>;
+
+    #[ink(message)]
+    fn cancel_topic_message(
+        &mut self,
+        topic: Topic,
+        id: QueueIndex,
+    ) -> Result<(), RollupClientError>;
+}
+
+#[ink::trait_definition]
+pub trait ConditionChecker {
+    #[ink(message)]
+    fn check_conditions(
+        &self,
+        conditions: Vec<(Key, Option<Value>)>,
+    ) -> Result<Vec<ConditionResult>, RollupClientError>;
+
+    #[ink(message)]
+    fn check_channel_conditions(
+        &self,
+        channel: ChannelId,
+        conditions: Vec<(Key, Option<Value>)>,
+    ) -> Result<Vec<ConditionResult>, RollupClientError>;
+}
+
 pub trait BaseRollupClient: MessageQueue + BaseAccessControl {
     fn inner_rollup_cond_eq(
         &mut self,
@@ -58,51 +204,599 @@ pub trait BaseRollupClient: MessageQueue + BaseAccessControl {
         updates: Vec<(Key, Option<Value>)>,
         actions: Vec<HandleActionInput>,
     ) -> Result<(), RollupClientError> {
-        self.inner_check_role(ATTESTOR_ROLE, attestor)?;
+        self.inner_check_role(self.attestor_role(), attestor)?;
+
+        // the keys managed by the library or the contract can't be used in the conditions or the updates
+        if conditions
+            .iter()
+            .chain(updates.iter())
+            .any(|(key, _)| self.is_reserved_key(key))
+        {
+            return Err(RollupClientError::ReservedKey);
+        }
 
         // check the conditions
-        for cond in conditions {
-            let key = cond.0;
-            let current_value = self.inner_get_value(&key);
-            let expected_value = cond.1;
-            match (current_value, expected_value) {
-                (None, None) => {}
-                (Some(v1), Some(v2)) => {
-                    if v1.ne(&v2) {
-                        // condition is not met
-                        return Err(RollupClientError::ConditionNotMet);
-                    }
-                }
-                (_, _) => return Err(RollupClientError::ConditionNotMet),
+        for (index, (key, expected_value)) in conditions.iter().enumerate() {
+            if self.inner_get_value(key).ne(expected_value) {
+                // condition is not met
+                return Err(RollupClientError::ConditionNotMet(index as u32));
             }
         }
 
         // apply the updates
+        let metadata = if self.keep_key_metadata() && !updates.is_empty() {
+            Some(self.new_key_metadata(attestor))
+        } else {
+            None
+        };
         for update in updates {
-            self.inner_set_value(&update.0, update.1.as_ref());
+            self.inner_set_value(&update.0, update.1.as_ref())?;
+            if let Some(metadata) = &metadata {
+                self.inner_set_value_metadata(&update.0, metadata);
+            }
         }
 
         // apply the actions
         for action in actions {
-            self.handle_action(action)?;
+            self.handle_action(attestor, action)?;
         }
 
-        Ok(())
+        let channel = self.channel_id();
+        self.on_rollup_applied(channel)
+    }
+
+    fn inner_get_queue_bounds(&self) -> Result<QueueBounds, RollupClientError> {
+        self.get_queue_bounds_of(self.queue_id())
+    }
+
+    fn inner_get_topic_queue_bounds(&self, topic: Topic) -> Result<QueueBounds, RollupClientError> {
+        self.get_queue_bounds_of(self.topic_queue_id(topic))
+    }
+
+    /// Encoded message not processed yet
+    fn inner_get_message(&self, id: QueueIndex) -> Result<Option<Vec<u8>>, RollupClientError> {
+        self.get_raw_message(self.queue_id(), id)
+    }
+
+    /// Encoded messages not processed yet, from the given index (at most `MAX_MESSAGES_BY_PAGE` messages)
+    fn inner_get_messages(
+        &self,
+        from: QueueIndex,
+        limit: QueueIndex,
+    ) -> Result<Vec<(QueueIndex, Vec<u8>)>, RollupClientError> {
+        self.inner_get_queue_messages(self.queue_id(), from, limit)
+    }
+
+    /// Encoded messages not processed yet in the queue of the given topic
+    fn inner_get_topic_messages(
+        &self,
+        topic: Topic,
+        from: QueueIndex,
+        limit: QueueIndex,
+    ) -> Result<Vec<(QueueIndex, Vec<u8>)>, RollupClientError> {
+        self.inner_get_queue_messages(self.topic_queue_id(topic), from, limit)
+    }
+
+    fn inner_get_queue_messages(
+        &self,
+        queue: QueueId,
+        from: QueueIndex,
+        limit: QueueIndex,
+    ) -> Result<Vec<(QueueIndex, Vec<u8>)>, RollupClientError> {
+        let bounds = self.get_queue_bounds_of(queue)?;
+        let from = from.max(bounds.head);
+        let to = from
+            .saturating_add(limit.min(MAX_MESSAGES_BY_PAGE))
+            .min(bounds.tail);
+        let mut messages = Vec::new();
+        for id in from..to {
+            if let Some(message) = self.get_raw_message(queue, id)? {
+                messages.push((id, message));
+            }
+        }
+        Ok(messages)
     }
 
-    fn handle_action(&mut self, input: HandleActionInput) -> Result<(), RollupClientError> {
+    /// Check the conditions without applying anything, so the worker can refresh only the stale keys
+    fn inner_check_conditions(
+        &self,
+        conditions: Vec<(Key, Option<Value>)>,
+    ) -> Result<Vec<ConditionResult>, RollupClientError> {
+        self.inner_check_channel_conditions(self.channel_id(), conditions)
+    }
+
+    /// Check the conditions in the given channel like `rollup_cond_eq` does: the reserved keys are rejected
+    fn inner_check_channel_conditions(
+        &self,
+        channel: ChannelId,
+        conditions: Vec<(Key, Option<Value>)>,
+    ) -> Result<Vec<ConditionResult>, RollupClientError> {
+        if conditions.iter().any(|(key, _)| self.is_reserved_key(key)) {
+            return Err(RollupClientError::ReservedKey);
+        }
+        let results = conditions
+            .into_iter()
+            .map(|(key, expected_value)| {
+                let current_value = self.inner_get_channel_value(channel, &key);
+                ConditionResult {
+                    is_met: current_value == expected_value,
+                    current_value,
+                }
+            })
+            .collect();
+        Ok(results)
+    }
+
+    fn handle_action(
+        &mut self,
+        attestor: Address,
+        input: HandleActionInput,
+    ) -> Result<(), RollupClientError> {
         match input {
-            HandleActionInput::Reply(action) => self.on_message_received(action)?,
-            HandleActionInput::SetQueueHead(id) => self.pop_to(id)?,
+            HandleActionInput::Reply(action) => {
+                // the reply is not bound to a message: the worker moves the head (action `SetQueueHead`)
+                // before sending the replies of the messages it has consumed.
+                // The leases are checked when the head is moved. The replies which must respect the deadline
+                // of the request are sent with the action `ReplyTo`.
+                self.handle_reply(None, action, Vec::new())?;
+            }
+            HandleActionInput::SetQueueHead(id) => {
+                self.check_pop_leases(attestor, id)?;
+                let queue = self.queue_id();
+                let head = self.get_queue_bounds_of(queue)?.head;
+                if id > head {
+                    self.on_messages_consumed(queue, attestor, head, id)?;
+                }
+                self.pop_to(id)?
+            }
             HandleActionInput::GrantAttestor(address) => {
-                self.inner_grant_role_unchecked(ATTESTOR_ROLE, address)?
+                self.inner_grant_role_unchecked(self.attestor_role(), address)?
             }
             HandleActionInput::RevokeAttestor(address) => {
-                self.inner_revoke_role_unchecked(ATTESTOR_ROLE, address)?
+                self.inner_revoke_role_unchecked(self.attestor_role(), address)?
+            }
+            HandleActionInput::SetTopicQueueHead { topic, id } => {
+                TopicQueue::new(self, topic).check_pop_leases(attestor, id)?;
+                let queue = self.topic_queue_id(topic);
+                let head = self.get_queue_bounds_of(queue)?.head;
+                if id > head {
+                    self.on_messages_consumed(queue, attestor, head, id)?;
+                }
+                TopicQueue::new(self, topic).pop_to(id)?
+            }
+            action @ (HandleActionInput::ExpireMessages(_)
+            | HandleActionInput::ReplyTo { .. }
+            | HandleActionInput::Ack(_)
+            | HandleActionInput::Lease { .. }
+            | HandleActionInput::FinalizeReplies(_)) => {
+                self.handle_queue_action(attestor, action)?
+            }
+            // the actions on a topic are handled in the queue of the topic, with the same checks
+            HandleActionInput::TopicReplyTo {
+                topic,
+                request_id,
+                payload,
+            } => TopicQueue::new(self, topic).handle_queue_action(
+                attestor,
+                HandleActionInput::ReplyTo {
+                    request_id,
+                    payload,
+                },
+            )?,
+            HandleActionInput::TopicAck { topic, ids } => TopicQueue::new(self, topic)
+                .handle_queue_action(attestor, HandleActionInput::Ack(ids))?,
+            HandleActionInput::TopicLease {
+                topic,
+                from,
+                to,
+                until_block,
+            } => TopicQueue::new(self, topic).handle_queue_action(
+                attestor,
+                HandleActionInput::Lease {
+                    from,
+                    to,
+                    until_block,
+                },
+            )?,
+            HandleActionInput::ExpireTopicMessages { topic, ids } => {
+                TopicQueue::new(self, topic)
+                    .handle_queue_action(attestor, HandleActionInput::ExpireMessages(ids))?
+            }
+            HandleActionInput::FinalizeTopicReplies { topic, request_id } => {
+                TopicQueue::new(self, topic)
+                    .handle_queue_action(attestor, HandleActionInput::FinalizeReplies(request_id))?
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle the actions on the messages of the queue (the queue of the topic for the topic actions)
+    fn handle_queue_action(
+        &mut self,
+        attestor: Address,
+        input: HandleActionInput,
+    ) -> Result<(), RollupClientError> {
+        match input {
+            HandleActionInput::ExpireMessages(ids) => {
+                for id in ids {
+                    self.inner_expire(id)?;
+                }
+            }
+            HandleActionInput::ReplyTo {
+                request_id,
+                payload,
+            } => {
+                self.check_message_lease(attestor, request_id)?;
+                // reject right away the replies that can't be handled
+                self.check_answerable(request_id)?;
+                let queue = self.queue_id();
+                if let Some(replies) = self.collect_reply(queue, attestor, request_id, payload)? {
+                    self.handle_replies(request_id, replies)?
+                }
+            }
+            HandleActionInput::Ack(ids) => {
+                for id in ids {
+                    self.check_message_lease(attestor, id)?;
+                    let queue = self.queue_id();
+                    let message = self
+                        .get_pending_message(queue, id)?
+                        .ok_or(RollupClientError::MessageNotPending)?;
+                    if self.get_message_status(id).is_none()
+                        && self.is_message_expired(&message, id)
+                    {
+                        // the deadline is passed: the message is closed as expired, not answered
+                        self.inner_expire(id)?;
+                        continue;
+                    }
+                    // the message has been processed by the attestor without reply.
+                    // The hook is called before the head is moved, so the message is not deleted yet.
+                    self.on_message_answered(queue, id, ink::prelude::vec![attestor])?;
+                    self.ack_message(id)?;
+                }
+            }
+            HandleActionInput::Lease {
+                from,
+                to,
+                until_block,
+            } => self.lease_messages(attestor, from, to, until_block)?,
+            HandleActionInput::FinalizeReplies(request_id) => {
+                self.check_message_lease(attestor, request_id)?;
+                let queue = self.queue_id();
+                let replies = self.take_timed_out_replies(queue, request_id)?;
+                self.handle_replies(request_id, replies)?
+            }
+            _ => return Err(RollupClientError::UnsupportedAction),
+        }
+        Ok(())
+    }
+
+    /// Handle the reply and return true if the contract accepts it. If the contract rejects it, the hook
+    /// `on_reply_rejected` decides if the batch is reverted or if the reply is kept (dead letter queue)
+    /// and the rest of the batch is applied.
+    fn handle_reply(
+        &mut self,
+        request: Option<(QueueIndex, Vec<u8>)>,
+        payload: Vec<u8>,
+        attestors: Vec<Address>,
+    ) -> Result<bool, RollupClientError> {
+        let queue = self.queue_id();
+        let Err(error) = self.dispatch_reply(queue, request.clone(), payload.clone()) else {
+            return Ok(true);
+        };
+        let channel = self.channel_id();
+        let (request_id, request) = request.unzip();
+        let letter = DeadLetter {
+            request_id,
+            queue,
+            request,
+            attestors,
+            payload,
+            error,
+            block_number: ::ink::env::block_number::<::ink::env::DefaultEnvironment>(),
+        };
+        self.on_reply_rejected(channel, letter)?;
+        Ok(false)
+    }
+
+    /// Handle the replies received for the request (a single reply if there is no quorum).
+    /// The request is marked as answered only if the contract accepts the reply.
+    fn handle_replies(
+        &mut self,
+        request_id: QueueIndex,
+        replies: Vec<CandidateReply>,
+    ) -> Result<(), RollupClientError> {
+        let request = self.check_answerable(request_id)?;
+        let payload = match replies.as_slice() {
+            [(_, reply)] => reply.clone(),
+            _ => {
+                let channel = self.channel_id();
+                match self.aggregate(channel, replies.clone()) {
+                    Ok(payload) => payload,
+                    // the replies don't agree: the request is closed so the rest of the batch is applied
+                    Err(error) => return self.inner_fail(request_id, error),
+                }
             }
+        };
+        // the attestors who have sent the reply handled (all of them if the replies have been combined)
+        let mut attestors: Vec<Address> = replies
+            .iter()
+            .filter(|(_, reply)| *reply == payload)
+            .map(|(attestor, _)| *attestor)
+            .collect();
+        if attestors.is_empty() {
+            attestors = replies.into_iter().map(|(attestor, _)| attestor).collect();
+        }
+
+        let request = Some((request_id, request.data));
+        if !self.handle_reply(request, payload, attestors.clone())? {
+            return Ok(());
+        }
+        self.answer_message(request_id)?;
+        let queue = self.queue_id();
+        self.on_message_answered(queue, request_id, attestors)
+    }
+
+    /// Handle again a reply rejected by the contract (see `BaseDeadLetterQueue`).
+    /// The request is answered if it is still pending (and the queue has not moved to a new epoch since).
+    fn retry_reply(&mut self, letter: DeadLetter) -> Result<(), RollupClientError> {
+        let request = letter.request_id.zip(letter.request);
+        self.dispatch_reply(letter.queue, request, letter.payload)?;
+        let Some(request_id) = letter.request_id else {
+            return Ok(());
+        };
+        // in a new epoch, the same index is used by another message
+        let topic = letter.queue.topic;
+        if letter.queue != self.topic_queue_id(topic) {
+            return Ok(());
+        }
+        let mut queue = TopicQueue::new(self, topic);
+        if queue.check_answerable(request_id).is_ok() {
+            queue.answer_message(request_id)?;
+            queue.on_message_answered(letter.queue, request_id, letter.attestors)?;
         }
         Ok(())
     }
 
+    /// Call the hook `on_reply` if the request is known, `on_message_received` otherwise
+    fn dispatch_reply(
+        &mut self,
+        queue: QueueId,
+        request: Option<(QueueIndex, Vec<u8>)>,
+        payload: Vec<u8>,
+    ) -> Result<(), RollupClientError> {
+        match request {
+            Some((id, request)) => {
+                if self.callback_requester(queue, id, &payload) {
+                    return Ok(());
+                }
+                self.on_reply(request, payload)
+            }
+            None => self.on_message_received(payload),
+        }
+    }
+
+    /// Mark the message as expired if its deadline is passed (anyone can do it)
+    fn inner_expire(&mut self, id: QueueIndex) -> Result<(), RollupClientError> {
+        let message = self.expire_message(id)?;
+        let queue = self.queue_id();
+        self.on_message_closed(queue, id)?;
+        let channel = self.channel_id();
+        self.on_message_expired(channel, id, message)
+    }
+
+    /// Close the request without reply because its replies can't be handled
+    fn inner_fail(
+        &mut self,
+        id: QueueIndex,
+        error: RollupClientError,
+    ) -> Result<(), RollupClientError> {
+        let message = self.fail_message(id, error)?;
+        let queue = self.queue_id();
+        self.on_message_closed(queue, id)?;
+        let channel = self.channel_id();
+        self.on_message_failed(channel, id, message)
+    }
+
+    /// Cancel a pending message (only the sender of the message or an admin can do it)
+    fn inner_cancel(&mut self, id: QueueIndex) -> Result<(), RollupClientError> {
+        let message = self
+            .get_pending_message(self.queue_id(), id)?
+            .ok_or(RollupClientError::MessageNotPending)?;
+        let caller = ::ink::env::caller();
+        if !message
+            .envelope
+            .is_some_and(|envelope| envelope.sender == caller)
+        {
+            only_role!(self, ADMIN_ROLE);
+        }
+        let message = self.cancel_message(id)?;
+        let queue = self.queue_id();
+        self.on_message_closed(queue, id)?;
+        let channel = self.channel_id();
+        self.on_message_cancelled(channel, id, message)
+    }
+
+    /// Mark the message of the topic queue as expired if its deadline is passed (anyone can do it)
+    fn inner_expire_topic_message(
+        &mut self,
+        topic: Topic,
+        id: QueueIndex,
+    ) -> Result<(), RollupClientError> {
+        TopicQueue::new(self, topic).inner_expire(id)
+    }
+
+    /// Cancel a pending message of the topic queue (only the sender of the message or an admin can do it)
+    fn inner_cancel_topic_message(
+        &mut self,
+        topic: Topic,
+        id: QueueIndex,
+    ) -> Result<(), RollupClientError> {
+        TopicQueue::new(self, topic).inner_cancel(id)
+    }
+
+    /// Override this method to keep, for each key, the last modification done by the rollup
+    fn keep_key_metadata(&self) -> bool {
+        false
+    }
+
+    /// Override this method to collect the replies of several attestors before handling them (see `BaseReplyQuorum`).
+    /// Return the replies to handle, or None while other replies are expected. By default, the reply is handled.
+    fn collect_reply(
+        &mut self,
+        _queue: QueueId,
+        attestor: Address,
+        _id: QueueIndex,
+        payload: Vec<u8>,
+    ) -> Result<Option<Vec<CandidateReply>>, RollupClientError> {
+        Ok(Some(ink::prelude::vec![(attestor, payload)]))
+    }
+
+    /// Override this method to handle the replies collected for a request when the quorum is not reached
+    /// (action `FinalizeReplies`, see `BaseReplyQuorum`)
+    fn take_timed_out_replies(
+        &mut self,
+        _queue: QueueId,
+        _id: QueueIndex,
+    ) -> Result<Vec<CandidateReply>, RollupClientError> {
+        Err(RollupClientError::QuorumNotReached)
+    }
+
+    /// Override this method to settle what is kept for a message (a fee, an occurrence of a schedule, ...)
+    /// when its reply is accepted (with the attestors who have sent the reply)
+    /// or when it is acknowledged (action `Ack`, with the attestor who has acknowledged it)
+    fn on_message_answered(
+        &mut self,
+        _queue: QueueId,
+        _id: QueueIndex,
+        _attestors: Vec<Address>,
+    ) -> Result<(), RollupClientError> {
+        Ok(())
+    }
+
+    /// Override this method to keep the attestor who has consumed the messages from `from` (included)
+    /// to `to` (excluded) by moving the head of the queue (see `BaseFeeEscrow::record_consumer`).
+    /// Called before the head is moved.
+    fn on_messages_consumed(
+        &mut self,
+        _queue: QueueId,
+        _attestor: Address,
+        _from: QueueIndex,
+        _to: QueueIndex,
+    ) -> Result<(), RollupClientError> {
+        Ok(())
+    }
+
+    /// Override this method to save the rejected replies instead of reverting the batch (see `BaseDeadLetterQueue`).
+    /// The contract should not modify its state before rejecting a reply.
+    fn on_reply_rejected(
+        &mut self,
+        _channel: ChannelId,
+        letter: DeadLetter,
+    ) -> Result<(), RollupClientError> {
+        Err(letter.error)
+    }
+
+    /// Override this method to send the reply to the contract which has pushed the request (see `BaseRequestHub`).
+    /// Return false if the reply must be handled by `on_reply`.
+    fn callback_requester(&mut self, _queue: QueueId, _id: QueueIndex, _response: &[u8]) -> bool {
+        false
+    }
+
+    /// Override this method to do some work at the end of each rollup transaction
+    /// (push the scheduled requests due now, see `BaseScheduledRequests`)
+    fn on_rollup_applied(&mut self, _channel: ChannelId) -> Result<(), RollupClientError> {
+        Ok(())
+    }
+
+    fn new_key_metadata(&mut self, attestor: Address) -> KeyMetadata {
+        let storage = KvStoreStorage::get_mut_storage(self);
+        let batch_sequence = storage
+            .batch_sequence
+            .get()
+            .unwrap_or_default()
+            .saturating_add(1);
+        storage.batch_sequence.set(&batch_sequence);
+
+        KeyMetadata {
+            block_number: ::ink::env::block_number::<::ink::env::DefaultEnvironment>(),
+            timestamp: ::ink::env::block_timestamp::<::ink::env::DefaultEnvironment>(),
+            attestor,
+            batch_sequence,
+        }
+    }
+
+    /// Role granted to the attestors (in each channel, the attestors of a channel are isolated from the other channels)
+    fn attestor_role(&self) -> RoleType {
+        ATTESTOR_ROLE
+    }
+
     fn on_message_received(&mut self, action: Vec<u8>) -> Result<(), RollupClientError>;
+
+    /// Override this method to handle the reply to a request (action `ReplyTo`) with the original request.
+    /// By default, the reply is handled by `on_message_received`.
+    fn on_reply(&mut self, _request: Vec<u8>, response: Vec<u8>) -> Result<(), RollupClientError> {
+        self.on_message_received(response)
+    }
+
+    /// Override this method to combine the replies sent by distinct attestors when the quorum is reached
+    /// (or the timeout is passed). By default, the reply sent by more than half of the attestors is handled
+    /// (see `BaseReplyQuorum::inner_aggregate` to require the majority of the quorum).
+    /// If an error is returned, the request is closed without reply (event `MessageFailed`).
+    fn aggregate(
+        &mut self,
+        _channel: ChannelId,
+        replies: Vec<CandidateReply>,
+    ) -> Result<Vec<u8>, RollupClientError> {
+        let nb_replies = u32::try_from(replies.len()).unwrap_or(u32::MAX);
+        majority_reply(&replies, nb_replies).ok_or(RollupClientError::QuorumNotReached)
+    }
+
+    /// Override this method to refund or clean up when a message is expired
+    fn on_message_expired(
+        &mut self,
+        _channel: ChannelId,
+        _id: QueueIndex,
+        _message: Vec<u8>,
+    ) -> Result<(), RollupClientError> {
+        Ok(())
+    }
+
+    /// Override this method to refund or clean up when a message is cancelled
+    fn on_message_cancelled(
+        &mut self,
+        _channel: ChannelId,
+        _id: QueueIndex,
+        _message: Vec<u8>,
+    ) -> Result<(), RollupClientError> {
+        Ok(())
+    }
+
+    /// Override this method to refund, clean up or notify the requester when the replies to a request don't agree
+    fn on_message_failed(
+        &mut self,
+        _channel: ChannelId,
+        _id: QueueIndex,
+        _message: Vec<u8>,
+    ) -> Result<(), RollupClientError> {
+        Ok(())
+    }
+
+    /// Override this method to handle the messages received on the other channels
+    fn on_channel_message_received(
+        &mut self,
+        _channel: ChannelId,
+        _action: Vec<u8>,
+    ) -> Result<(), RollupClientError> {
+        Err(RollupClientError::UnsupportedAction)
+    }
+
+    /// Override this method to handle the replies to the requests of the other channels
+    fn on_channel_reply(
+        &mut self,
+        channel: ChannelId,
+        _request: Vec<u8>,
+        response: Vec<u8>,
+    ) -> Result<(), RollupClientError> {
+        self.on_channel_message_received(channel, response)
+    }
 }
@@ -0,0 +1,340 @@
+use crate::only_role;
+use crate::traits::access_control::{BaseAccessControl, ADMIN_ROLE};
+use crate::traits::channel::{ChannelId, RollupChannel};
+use crate::traits::kv_store::BlockNumber;
+use crate::traits::message_queue::{MessageQueue, PushOptions, QueueId, QueueIndex};
+use crate::traits::rollup_client::BaseRollupClient;
+use crate::traits::RollupClientError;
+use ink::prelude::vec::Vec;
+use ink::storage::Mapping;
+
+pub type ScheduleId = u32;
+pub type Occurrence = u32;
+
+/// Max number of active schedules by channel
+pub const MAX_ACTIVE_SCHEDULES: u32 = 20;
+/// Max number of due requests pushed at the end of a rollup transaction
+pub const MAX_DUE_REQUESTS_BY_ROLLUP: u32 = 5;
+
+/// Request pushed in the queue `count` times, every `interval` blocks from the block `start`
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct Schedule {
+    pub payload: Vec<u8>,
+    pub start: BlockNumber,
+    pub interval: BlockNumber,
+    pub count: Occurrence,
+    /// next occurrence to push in the queue
+    pub next_occurrence: Occurrence,
+    /// occurrences answered by the attestors
+    pub completed: Occurrence,
+    /// occurrences not pushed before the next one was due
+    pub missed: Occurrence,
+}
+
+impl Schedule {
+    fn due_block(&self, occurrence: Occurrence) -> Option<BlockNumber> {
+        self.interval
+            .checked_mul(occurrence)
+            .and_then(|offset| self.start.checked_add(offset))
+    }
+
+    /// Last occurrence due at the given block
+    fn last_due_occurrence(&self, block_number: BlockNumber) -> Option<Occurrence> {
+        if self.next_occurrence >= self.count
+            || block_number < self.due_block(self.next_occurrence)?
+        {
+            return None;
+        }
+        let last = block_number
+            .saturating_sub(self.start)
+            .checked_div(self.interval)
+            .unwrap_or(Occurrence::MAX);
+        Some(last.min(self.count.saturating_sub(1)))
+    }
+}
+
+/// Message pushed in the queue when an occurrence is due
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub struct ScheduledRequest {
+    pub schedule_id: ScheduleId,
+    pub occurrence: Occurrence,
+    pub payload: Vec<u8>,
+}
+
+#[derive(Default, Debug)]
+#[ink::storage_item]
+pub struct ScheduleData {
+    pub schedules: Mapping<(ChannelId, ScheduleId), Schedule>,
+    pub next_id: Mapping<ChannelId, ScheduleId>,
+    /// schedules with occurrences not pushed yet
+    pub active: Mapping<ChannelId, Vec<ScheduleId>>,
+    /// occurrence of the messages pushed by a schedule
+    pub occurrences: Mapping<(QueueId, QueueIndex), (ScheduleId, Occurrence)>,
+}
+
+impl ScheduleData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+pub trait ScheduleStorage {
+    fn get_storage(&self) -> &ScheduleData;
+    fn get_mut_storage(&mut self) -> &mut ScheduleData;
+}
+
+/// Event emitted when a request is scheduled
+#[ink::event]
+pub struct RequestScheduled {
+    #[ink(topic)]
+    schedule_id: ScheduleId,
+    start: BlockNumber,
+    interval: BlockNumber,
+    count: Occurrence,
+}
+
+/// Event emitted when an occurrence is pushed in the queue
+#[ink::event]
+pub struct ScheduledRequestQueued {
+    #[ink(topic)]
+    schedule_id: ScheduleId,
+    occurrence: Occurrence,
+    id: QueueIndex,
+}
+
+/// Event emitted when occurrences have not been pushed before the next one was due
+#[ink::event]
+pub struct ScheduledRequestsMissed {
+    #[ink(topic)]
+    schedule_id: ScheduleId,
+    from: Occurrence,
+    to: Occurrence,
+}
+
+/// Event emitted when the reply to an occurrence is received
+#[ink::event]
+pub struct ScheduledRequestCompleted {
+    #[ink(topic)]
+    schedule_id: ScheduleId,
+    occurrence: Occurrence,
+}
+
+/// Event emitted when a schedule is cancelled
+#[ink::event]
+pub struct ScheduleCancelled {
+    #[ink(topic)]
+    schedule_id: ScheduleId,
+}
+
+#[ink::trait_definition]
+pub trait ScheduledRequests {
+    #[ink(message)]
+    fn get_schedule(&self, schedule_id: ScheduleId) -> Option<Schedule>;
+
+    /// Push in the queue the requests due at the current block (anyone can do it)
+    #[ink(message)]
+    fn push_due_requests(&mut self, max: u32) -> Result<u32, RollupClientError>;
+
+    #[ink(message)]
+    fn cancel_schedule(&mut self, schedule_id: ScheduleId) -> Result<(), RollupClientError>;
+}
+
+pub trait BaseScheduledRequests: BaseRollupClient + ScheduleStorage {
+    /// Push the request in the queue `count` times, every `interval` blocks from the block `start`
+    fn schedule_request(
+        &mut self,
+        payload: Vec<u8>,
+        start: BlockNumber,
+        interval: BlockNumber,
+        count: Occurrence,
+    ) -> Result<ScheduleId, RollupClientError> {
+        if count == 0 || (count > 1 && interval == 0) {
+            return Err(RollupClientError::InvalidSchedule);
+        }
+        let channel = self.channel_id();
+        let storage = ScheduleStorage::get_mut_storage(self);
+        let mut active = storage.active.get(channel).unwrap_or_default();
+        if active.len() >= MAX_ACTIVE_SCHEDULES as usize {
+            return Err(RollupClientError::TooManySchedules);
+        }
+
+        let schedule = Schedule {
+            payload,
+            start,
+            interval,
+            count,
+            next_occurrence: 0,
+            completed: 0,
+            missed: 0,
+        };
+        // the last occurrence must be reachable
+        schedule
+            .due_block(count.saturating_sub(1))
+            .ok_or(RollupClientError::InvalidSchedule)?;
+
+        let schedule_id = storage.next_id.get(channel).unwrap_or_default();
+        storage.next_id.insert(
+            channel,
+            &schedule_id
+                .checked_add(1)
+                .ok_or(RollupClientError::InvalidSchedule)?,
+        );
+        storage.schedules.insert((channel, schedule_id), &schedule);
+        active.push(schedule_id);
+        storage.active.insert(channel, &active);
+
+        ::ink::env::emit_event(RequestScheduled {
+            schedule_id,
+            start,
+            interval,
+            count,
+        });
+        Ok(schedule_id)
+    }
+
+    fn inner_get_schedule(&self, schedule_id: ScheduleId) -> Option<Schedule> {
+        ScheduleStorage::get_storage(self)
+            .schedules
+            .get((self.channel_id(), schedule_id))
+    }
+
+    fn inner_push_due_requests(&mut self, max: u32) -> Result<u32, RollupClientError> {
+        let channel = self.channel_id();
+        self.push_channel_due_requests(channel, max)
+    }
+
+    /// Push the requests due at the end of a rollup transaction, so the workers can read them for the next one.
+    /// Call this method in the hook `on_rollup_applied` to push the scheduled requests without a keeper.
+    /// The errors are ignored so a queue which can't receive the requests doesn't block the rollup,
+    /// the requests can still be pushed with the message `push_due_requests`.
+    fn push_due_requests_on_rollup(&mut self, channel: ChannelId) -> Result<(), RollupClientError> {
+        let _ = self.push_channel_due_requests(channel, MAX_DUE_REQUESTS_BY_ROLLUP);
+        Ok(())
+    }
+
+    /// Push in the queue of the channel the occurrences due at the current block (at most `max`).
+    /// When several occurrences of a schedule are due, only the last one is pushed, the other ones are missed.
+    /// The requests are pushed on behalf of the contract (sender of the envelope), so only an admin can cancel them.
+    /// Return the number of requests pushed.
+    fn push_channel_due_requests(
+        &mut self,
+        channel: ChannelId,
+        max: u32,
+    ) -> Result<u32, RollupClientError> {
+        let active = ScheduleStorage::get_storage(self)
+            .active
+            .get(channel)
+            .unwrap_or_default();
+        if active.is_empty() {
+            return Ok(0);
+        }
+
+        let block_number = ::ink::env::block_number::<::ink::env::DefaultEnvironment>();
+        let mut pushed = 0;
+        let mut still_active = Vec::new();
+        for schedule_id in active {
+            let Some(mut schedule) = ScheduleStorage::get_storage(self)
+                .schedules
+                .get((channel, schedule_id))
+            else {
+                continue;
+            };
+            if pushed < max {
+                if let Some(occurrence) = schedule.last_due_occurrence(block_number) {
+                    if occurrence > schedule.next_occurrence {
+                        schedule.missed = schedule
+                            .missed
+                            .saturating_add(occurrence.saturating_sub(schedule.next_occurrence));
+                        ::ink::env::emit_event(ScheduledRequestsMissed {
+                            schedule_id,
+                            from: schedule.next_occurrence,
+                            to: occurrence,
+                        });
+                    }
+                    let request = ScheduledRequest {
+                        schedule_id,
+                        occurrence,
+                        payload: schedule.payload.clone(),
+                    };
+                    let options = PushOptions {
+                        sender: Some(::ink::env::address()),
+                        ..Default::default()
+                    };
+                    let id = RollupChannel::new(self, channel)
+                        .push_message_with_options(&request, options)?;
+                    let queue = self.channel_queue_id(channel);
+                    let storage = ScheduleStorage::get_mut_storage(self);
+                    storage
+                        .occurrences
+                        .insert((queue, id), &(schedule_id, occurrence));
+                    schedule.next_occurrence = occurrence.saturating_add(1);
+                    storage.schedules.insert((channel, schedule_id), &schedule);
+                    ::ink::env::emit_event(ScheduledRequestQueued {
+                        schedule_id,
+                        occurrence,
+                        id,
+                    });
+                    pushed = pushed.saturating_add(1);
+                }
+            }
+            if schedule.next_occurrence < schedule.count {
+                still_active.push(schedule_id);
+            }
+        }
+        ScheduleStorage::get_mut_storage(self)
+            .active
+            .insert(channel, &still_active);
+        Ok(pushed)
+    }
+
+    /// Count the reply to the message if it has been pushed by a schedule
+    fn complete_scheduled_request(&mut self, queue: QueueId, id: QueueIndex) {
+        let storage = ScheduleStorage::get_mut_storage(self);
+        let Some((schedule_id, occurrence)) = storage.occurrences.take((queue, id)) else {
+            return;
+        };
+        if let Some(mut schedule) = storage.schedules.get((queue.channel, schedule_id)) {
+            schedule.completed = schedule.completed.saturating_add(1);
+            storage
+                .schedules
+                .insert((queue.channel, schedule_id), &schedule);
+        }
+        ::ink::env::emit_event(ScheduledRequestCompleted {
+            schedule_id,
+            occurrence,
+        });
+    }
+
+    /// Forget the occurrence of a message closed without reply (expired, cancelled, skipped, ...).
+    /// Call this method in the hook `on_message_closed` so the occurrences are not kept forever.
+    fn remove_scheduled_request(
+        &mut self,
+        queue: QueueId,
+        id: QueueIndex,
+    ) -> Option<(ScheduleId, Occurrence)> {
+        ScheduleStorage::get_mut_storage(self)
+            .occurrences
+            .take((queue, id))
+    }
+
+    /// Stop pushing the occurrences of the schedule (only an admin can do it).
+    /// The occurrences already in the queue are not cancelled.
+    fn inner_cancel_schedule(&mut self, schedule_id: ScheduleId) -> Result<(), RollupClientError> {
+        only_role!(self, ADMIN_ROLE);
+        let channel = self.channel_id();
+        let storage = ScheduleStorage::get_mut_storage(self);
+        let mut active = storage.active.get(channel).unwrap_or_default();
+        let Some(index) = active.iter().position(|id| *id == schedule_id) else {
+            return Err(RollupClientError::ScheduleNotFound);
+        };
+        active.remove(index);
+        storage.active.insert(channel, &active);
+        storage.schedules.remove((channel, schedule_id));
+
+        ::ink::env::emit_event(ScheduleCancelled { schedule_id });
+        Ok(())
+    }
+}
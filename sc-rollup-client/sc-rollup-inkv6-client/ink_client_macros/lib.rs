@@ -0,0 +1,58 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, LitInt, LitStr};
+
+/// Implement `TypedMessage` for the type.
+///
+/// By default, the id of the message is derived from the name of the type and the version is 1.
+/// Use the attribute `#[typed_message(name = "...", id = ..., version = ...)]` to override them,
+/// for example to keep the same id for the versions `PriceResponseV1` and `PriceResponseV2`.
+#[proc_macro_derive(TypedMessage, attributes(typed_message))]
+pub fn derive_typed_message(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match typed_message(&input) {
+        Ok(output) => output.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn typed_message(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut name = LitStr::new(&input.ident.to_string(), input.ident.span());
+    let mut id: Option<LitInt> = None;
+    let mut version = LitInt::new("1", input.ident.span());
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("typed_message"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = meta.value()?.parse()?;
+            } else if meta.path.is_ident("id") {
+                id = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("version") {
+                version = meta.value()?.parse()?;
+            } else {
+                return Err(meta.error("expected `name`, `id` or `version`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let type_id = match id {
+        Some(id) => quote!(#id),
+        None => quote!(::ink::selector_id!(#name)),
+    };
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::inkv6_client_lib::traits::message_router::TypedMessage
+            for #ident #ty_generics #where_clause
+        {
+            const TYPE_ID: ::inkv6_client_lib::traits::message_router::MessageTypeId = #type_id;
+            const VERSION: ::inkv6_client_lib::traits::message_router::SchemaVersion = #version;
+        }
+    })
+}
@@ -0,0 +1,46 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// Contract sending its requests to the request hub, used to test the callbacks
+#[ink::contract]
+pub mod hub_requester {
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use inkv6_client_lib::traits::message_queue::QueueIndex;
+    use inkv6_client_lib::traits::request_hub::RequestHub;
+    use inkv6_client_lib::traits::RollupClientError;
+
+    #[ink(storage)]
+    pub struct HubRequester {
+        hub: Address,
+        responses: Mapping<QueueIndex, Vec<u8>>,
+    }
+
+    impl HubRequester {
+        #[ink(constructor)]
+        pub fn new(hub: Address) -> Self {
+            Self {
+                hub,
+                responses: Mapping::default(),
+            }
+        }
+
+        /// Send the request to the hub, the response is sent to `on_response`
+        #[ink(message)]
+        pub fn request(&mut self, payload: Vec<u8>) -> Result<QueueIndex, RollupClientError> {
+            let mut hub: ink::contract_ref!(RequestHub) = self.hub.into();
+            hub.request(payload, ink::selector_bytes!("on_response"))
+        }
+
+        /// Callback called by the hub with the reply
+        #[ink(message)]
+        pub fn on_response(&mut self, id: QueueIndex, response: Vec<u8>) {
+            assert_eq!(self.hub, self.env().caller(), "only the hub can call back");
+            self.responses.insert(id, &response);
+        }
+
+        #[ink(message)]
+        pub fn get_response(&self, id: QueueIndex) -> Option<Vec<u8>> {
+            self.responses.get(id)
+        }
+    }
+}
@@ -3,6 +3,7 @@ use ink_e2e::{ContractsBackend, E2EBackend, InstantiationResult};
 use ink::scale::Encode;
 
 use ink_client::{ink_client};
+use hub_requester::{hub_requester};
 
 use inkv6_client_lib::traits::access_control::{AccessControl};
 use inkv6_client_lib::traits::meta_transaction::{MetaTransaction};
@@ -357,3 +358,165 @@ async fn test_meta_tx_rollup_cond_eq(mut client: ink_e2e::Client<C, E>) -> E2ERe
 
     Ok(())
 }
+
+/// Test the request hub
+/// Alice is the owner and the attestor
+/// Bob asks the requester contract to send the request
+///
+#[ink_e2e::test]
+async fn test_request_hub_callback(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+    let contract = alice_instantiates_client(&mut client).await;
+
+    let mut requester_constructor = hub_requester::HubRequesterRef::new(contract.addr);
+    let requester = client
+        .instantiate(
+            "hub_requester",
+            &ink_e2e::alice(),
+            &mut requester_constructor
+        )
+        .submit()
+        .await
+        .expect("instantiate the requester failed");
+
+    // the requester contract sends the request to the hub
+    let request =
+        requester.call_builder::<hub_requester::HubRequester>()
+            .request(b"BTC/USD".to_vec());
+    let request_id = client
+        .call(&ink_e2e::bob(), &request)
+        .submit()
+        .await
+        .expect("request failed")
+        .return_value()
+        .expect("request rejected by the hub");
+
+    // the attestor replies to the request
+    let response = 58u128.encode();
+    let actions = vec![HandleActionInput::ReplyTo {
+        request_id,
+        payload: response.clone(),
+    }];
+    let rollup_cond_eq =
+        contract.call_builder::<ink_client::InkClient>()
+            .rollup_cond_eq(vec![], vec![], actions);
+    client.call(&ink_e2e::alice(), &rollup_cond_eq)
+        .submit()
+        .await
+        .expect("reply failed");
+
+    // the requester contract has been called back with the reply
+    let get_response =
+        requester.call_builder::<hub_requester::HubRequester>()
+            .get_response(request_id);
+    let result = client
+        .call(&ink_e2e::alice(), &get_response)
+        .dry_run()
+        .await
+        .expect("fail to query the response")
+        .return_value();
+    assert_eq!(Some(response), result);
+
+    Ok(())
+}
+
+/// Weight of the transaction estimated by a dry run: (ref time, proof size)
+type Weight = (u64, u64);
+
+fn print_weight(operation: &str, weight: Weight, legacy_weight: Weight) {
+    println!(
+        "{operation}: ref time {} (previous layout {}), proof size {} (previous layout {})",
+        weight.0, legacy_weight.0, weight.1, legacy_weight.1
+    );
+}
+
+///
+/// Benchmark the weight of a push and a pop with the dedicated queue storage
+/// against the previous layout (queue saved in the key value store).
+/// The operations of the previous layout are replayed with the updates of a rollup transaction,
+/// so the weight of an empty rollup transaction is subtracted.
+/// The pop includes the hooks called for the deleted message (ie `on_message_closed`).
+/// Run with `cargo test --features e2e-tests bench_push_and_pop -- --nocapture` to print the weights.
+///
+#[ink_e2e::test]
+async fn bench_push_and_pop(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+    let contract = alice_instantiates_client(&mut client).await;
+
+    macro_rules! dry_run_weight {
+        ($message:expr) => {{
+            let result = client
+                .call(&ink_e2e::alice(), &$message)
+                .dry_run()
+                .await
+                .expect("dry run failed");
+            assert!(!result.is_err(), "the call is reverted");
+            let weight = result.exec_result.gas_required;
+            (weight.ref_time(), weight.proof_size())
+        }};
+    }
+    macro_rules! rollup_cond_eq {
+        ($conditions:expr, $updates:expr, $actions:expr) => {
+            contract
+                .call_builder::<ink_client::InkClient>()
+                .rollup_cond_eq($conditions, $updates, $actions)
+        };
+    }
+
+    // the first message initializes the queue in both layouts
+    alice_push_message(&mut client, &contract).await;
+    let tail_key = b"bench/_tail".to_vec();
+    let head_key = b"bench/_head".to_vec();
+    let message_key = |id: u32| [b"bench/".as_slice(), &id.encode()].concat();
+    let first_push = rollup_cond_eq!(
+        vec![(tail_key.clone(), None)],
+        vec![
+            (message_key(0), Some(vec![0])),
+            (tail_key.clone(), Some(1u32.encode())),
+        ],
+        vec![]
+    );
+    client
+        .call(&ink_e2e::alice(), &first_push)
+        .submit()
+        .await
+        .expect("first push failed");
+
+    let empty_rollup = dry_run_weight!(rollup_cond_eq!(vec![], vec![], vec![]));
+    let without_rollup = |weight: Weight| {
+        (
+            weight.0.saturating_sub(empty_rollup.0),
+            weight.1.saturating_sub(empty_rollup.1),
+        )
+    };
+
+    // push: read the tail, write the message and the tail
+    let push = dry_run_weight!(contract
+        .call_builder::<ink_client::InkClient>()
+        .push_message(vec![0]));
+    let legacy_push = without_rollup(dry_run_weight!(rollup_cond_eq!(
+        vec![(tail_key.clone(), Some(1u32.encode()))],
+        vec![
+            (message_key(1), Some(vec![0])),
+            (tail_key.clone(), Some(2u32.encode())),
+        ],
+        vec![]
+    )));
+    print_weight("push", push, legacy_push);
+
+    // pop: read the head and the tail, delete the message and write the head
+    let pop = without_rollup(dry_run_weight!(rollup_cond_eq!(
+        vec![],
+        vec![],
+        vec![HandleActionInput::SetQueueHead(1)]
+    )));
+    let legacy_pop = without_rollup(dry_run_weight!(rollup_cond_eq!(
+        vec![
+            (head_key.clone(), None),
+            (tail_key.clone(), Some(1u32.encode())),
+        ],
+        vec![(message_key(0), None), (head_key.clone(), Some(1u32.encode()))],
+        vec![]
+    )));
+    print_weight("pop", pop, legacy_pop);
+
+    Ok(())
+}
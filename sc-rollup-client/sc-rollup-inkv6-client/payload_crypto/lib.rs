@@ -0,0 +1,97 @@
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, Payload};
+use chacha20poly1305::ChaCha20Poly1305;
+use hkdf::Hkdf;
+use inkv6_client_lib::traits::encrypted_payload::{EncryptedPayload, PublicKey, WorkerPublicKey};
+use rand_core::OsRng;
+use sha2::Sha256;
+use x25519_dalek::{EphemeralSecret, SharedSecret};
+
+pub use x25519_dalek::StaticSecret;
+
+const KEY_INFO: &[u8] = b"sc-rollup/encrypted-payload/v1";
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Error {
+    /// the public key is a low order point
+    InvalidPublicKey,
+    EncryptionFailed,
+    /// wrong secret key or the payload has been tampered with
+    DecryptionFailed,
+}
+
+/// Generate the key pair of the worker. The public key is registered in the contract.
+pub fn generate_key_pair() -> (StaticSecret, PublicKey) {
+    let secret = StaticSecret::random_from_rng(OsRng);
+    let public_key = x25519_dalek::PublicKey::from(&secret).to_bytes();
+    (secret, public_key)
+}
+
+/// Encrypt the payload for the worker with a new ephemeral key
+pub fn encrypt(worker_key: &WorkerPublicKey, plaintext: &[u8]) -> Result<EncryptedPayload, Error> {
+    let ephemeral_secret = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_public_key = x25519_dalek::PublicKey::from(&ephemeral_secret).to_bytes();
+    let shared_secret =
+        ephemeral_secret.diffie_hellman(&x25519_dalek::PublicKey::from(worker_key.public_key));
+
+    let cipher = cipher(
+        &shared_secret,
+        &ephemeral_public_key,
+        &worker_key.public_key,
+    )?;
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad: &worker_key.id.to_le_bytes(),
+            },
+        )
+        .map_err(|_| Error::EncryptionFailed)?;
+
+    Ok(EncryptedPayload {
+        key_id: worker_key.id,
+        ephemeral_public_key,
+        nonce: nonce.into(),
+        ciphertext,
+    })
+}
+
+/// Decrypt the payload with the secret key of the worker
+pub fn decrypt(secret: &StaticSecret, payload: &EncryptedPayload) -> Result<Vec<u8>, Error> {
+    let public_key = x25519_dalek::PublicKey::from(secret).to_bytes();
+    let shared_secret =
+        secret.diffie_hellman(&x25519_dalek::PublicKey::from(payload.ephemeral_public_key));
+
+    let cipher = cipher(&shared_secret, &payload.ephemeral_public_key, &public_key)?;
+    cipher
+        .decrypt(
+            &payload.nonce.into(),
+            Payload {
+                msg: &payload.ciphertext,
+                aad: &payload.key_id.to_le_bytes(),
+            },
+        )
+        .map_err(|_| Error::DecryptionFailed)
+}
+
+/// Derive the symmetric key from the shared secret and both public keys
+fn cipher(
+    shared_secret: &SharedSecret,
+    ephemeral_public_key: &PublicKey,
+    worker_public_key: &PublicKey,
+) -> Result<ChaCha20Poly1305, Error> {
+    if !shared_secret.was_contributory() {
+        return Err(Error::InvalidPublicKey);
+    }
+    let salt = [
+        ephemeral_public_key.as_slice(),
+        worker_public_key.as_slice(),
+    ]
+    .concat();
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&salt), shared_secret.as_bytes())
+        .expand(KEY_INFO, &mut key)
+        .map_err(|_| Error::EncryptionFailed)?;
+    Ok(ChaCha20Poly1305::new(&key.into()))
+}
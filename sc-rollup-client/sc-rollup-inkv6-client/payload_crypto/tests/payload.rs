@@ -0,0 +1,56 @@
+use inkv6_client_lib::traits::encrypted_payload::WorkerPublicKey;
+use inkv6_payload_crypto::{decrypt, encrypt, generate_key_pair, Error};
+
+#[test]
+fn test_encrypt_decrypt() {
+    let (secret, public_key) = generate_key_pair();
+    let worker_key = WorkerPublicKey { id: 3, public_key };
+
+    let payload = encrypt(&worker_key, b"secret bid").unwrap();
+    assert_eq!(3, payload.key_id);
+    assert_ne!(b"secret bid".to_vec(), payload.ciphertext);
+    assert_eq!(Ok(b"secret bid".to_vec()), decrypt(&secret, &payload));
+
+    // a new ephemeral key and nonce are used for each payload
+    let other_payload = encrypt(&worker_key, b"secret bid").unwrap();
+    assert_ne!(payload.ciphertext, other_payload.ciphertext);
+}
+
+#[test]
+fn test_decrypt_with_wrong_key() {
+    let (_, public_key) = generate_key_pair();
+    let (other_secret, _) = generate_key_pair();
+    let payload = encrypt(&WorkerPublicKey { id: 0, public_key }, b"secret bid").unwrap();
+
+    assert_eq!(
+        Err(Error::DecryptionFailed),
+        decrypt(&other_secret, &payload)
+    );
+}
+
+#[test]
+fn test_tampered_payload() {
+    let (secret, public_key) = generate_key_pair();
+    let payload = encrypt(&WorkerPublicKey { id: 0, public_key }, b"secret bid").unwrap();
+
+    let mut tampered = payload.clone();
+    tampered.ciphertext[0] ^= 1;
+    assert_eq!(Err(Error::DecryptionFailed), decrypt(&secret, &tampered));
+
+    // the key id is authenticated
+    let mut tampered = payload;
+    tampered.key_id = 1;
+    assert_eq!(Err(Error::DecryptionFailed), decrypt(&secret, &tampered));
+}
+
+#[test]
+fn test_low_order_public_key() {
+    let worker_key = WorkerPublicKey {
+        id: 0,
+        public_key: [0; 32],
+    };
+    assert_eq!(
+        Err(Error::InvalidPublicKey),
+        encrypt(&worker_key, b"secret bid")
+    );
+}